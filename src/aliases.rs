@@ -0,0 +1,171 @@
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::error::Error;
+use std::io;
+use std::path::Path;
+
+// The built-in corrections, versioned with the code.
+const DEFAULT_ALIASES: &str = include_str!("../aliases.csv");
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum AliasKind {
+    Dance,
+    Professional,
+    Moniker,
+    Redirect,
+}
+
+#[derive(Debug, Deserialize)]
+struct Alias {
+    kind: AliasKind,
+    name: String,
+    replacement: String,
+    #[serde(default)]
+    note: String,
+}
+
+/// Corrections for names on the Wikipedia pages that are misspelled,
+/// inconsistent between series, or cannot be matched up by the usual rules.
+/// They are read from a CSV file with the columns `kind`, `name`,
+/// `replacement` and `note`; see `aliases.csv` for the built-in ones.
+#[derive(Debug, Clone)]
+pub struct Aliases {
+    // Keyed by the lowercase dance name.
+    dances: HashMap<String, String>,
+    // The name to use and a note for the rows.
+    professionals: HashMap<String, (String, String)>,
+    monikers: HashMap<String, String>,
+    // Wikipedia redirects to the articles they go to.
+    redirects: HashMap<String, String>,
+}
+impl Aliases {
+    /// The built-in aliases, with the aliases in the file at `path` added to
+    /// them. An alias in the file replaces a built-in alias for the same name.
+    pub fn load(path: &Path) -> Result<Aliases, Box<dyn Error>> {
+        let mut aliases = Aliases::default();
+        aliases.add_csv(std::fs::File::open(path)?)?;
+        Ok(aliases)
+    }
+
+    fn add_csv<R: io::Read>(&mut self, rdr: R) -> Result<(), csv::Error> {
+        let mut rdr = csv::ReaderBuilder::new()
+            .comment(Some(b'#'))
+            .trim(csv::Trim::All)
+            .from_reader(rdr);
+        for alias in rdr.deserialize() {
+            let alias: Alias = alias?;
+            match alias.kind {
+                AliasKind::Dance => {
+                    self.dances
+                        .insert(alias.name.to_lowercase(), alias.replacement);
+                }
+                AliasKind::Professional => {
+                    self.professionals
+                        .insert(alias.name, (alias.replacement, alias.note));
+                }
+                AliasKind::Moniker => {
+                    self.monikers.insert(alias.name, alias.replacement);
+                }
+                AliasKind::Redirect => {
+                    self.redirects.insert(alias.name, alias.replacement);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// The dance meant by a dance name on a page.
+    pub fn dance<'a>(&'a self, name: &'a str) -> &'a str {
+        self.dances
+            .get(&name.to_lowercase())
+            .map_or(name, String::as_str)
+    }
+
+    /// The name to use for a professional, and a note for their rows if the
+    /// name was replaced.
+    pub fn professional<'a>(&'a self, name: &'a str) -> (&'a str, Option<&'a str>) {
+        match self.professionals.get(name) {
+            Some((replacement, note)) if note.is_empty() => (replacement, None),
+            Some((replacement, note)) => (replacement, Some(note)),
+            None => (name, None),
+        }
+    }
+
+    /// The short name of a celebrity in the Week tables, if it is not one of
+    /// the usual forms of their full name.
+    pub fn moniker(&self, full_name: &str) -> Option<&str> {
+        self.monikers.get(full_name).map(String::as_str)
+    }
+
+    /// The article a Wikipedia redirect goes to, e.g. "Karen_Clifton" to
+    /// "Karen_Hauer", if it is known.
+    pub fn redirect(&self, title: &str) -> Option<&str> {
+        self.redirects.get(title).map(String::as_str)
+    }
+}
+impl Default for Aliases {
+    fn default() -> Self {
+        let mut aliases = Aliases {
+            dances: HashMap::new(),
+            professionals: HashMap::new(),
+            monikers: HashMap::new(),
+            redirects: HashMap::new(),
+        };
+        // The built-in file is checked by the tests, so it always parses.
+        aliases
+            .add_csv(DEFAULT_ALIASES.as_bytes())
+            .expect("built-in aliases are valid");
+        aliases
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::error::Error;
+
+    use super::Aliases;
+
+    #[test]
+    fn test_aliases() -> Result<(), Box<dyn Error>> {
+        let aliases = Aliases::default();
+        assert_eq!(aliases.dance("street"), "Street/Commercial");
+        assert_eq!(aliases.dance("Jazz"), "Theatre/Jazz");
+        assert_eq!(aliases.dance("Waltz"), "Waltz");
+        assert_eq!(
+            aliases.professional("Anton du Beke"),
+            ("Anton Du Beke", None)
+        );
+        assert_eq!(
+            aliases.professional("Karen Clifton"),
+            ("Karen Hauer", Some("Karen danced as Karen Clifton"))
+        );
+        assert_eq!(aliases.moniker("DJ Spoony"), Some("Spoony"));
+        assert_eq!(aliases.moniker("Kelvin Fletcher"), None);
+        assert_eq!(aliases.redirect("Karen_Clifton"), Some("Karen_Hauer"));
+        assert_eq!(aliases.redirect("Karen_Hauer"), None);
+
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("aliases.csv");
+        std::fs::write(
+            &path,
+            "# Local corrections\n\
+            kind,name,replacement,note\n\
+            dance,Jazz,Contemporary,\n\
+            professional,Aljaz Skorjanec,Aljaž Škorjanec,\n\
+            professional,Kristina Rihanoff,Kristina Rihanoff,Kristina's last series\n",
+        )?;
+        let loaded = Aliases::load(&path)?;
+        assert_eq!(loaded.dance("Jazz"), "Contemporary");
+        assert_eq!(loaded.dance("Street"), "Street/Commercial");
+        assert_eq!(
+            loaded.professional("Aljaz Skorjanec"),
+            ("Aljaž Škorjanec", None)
+        );
+        assert_eq!(
+            loaded.professional("Kristina Rihanoff"),
+            ("Kristina Rihanoff", Some("Kristina's last series"))
+        );
+        Ok(())
+    }
+}
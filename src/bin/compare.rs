@@ -4,13 +4,7 @@ use std::error::Error;
 use std::fs::File;
 use std::io::BufReader;
 use std::path::Path;
-
-#[derive(Debug, Deserialize)]
-pub(crate) struct Row {
-    series: u16,
-    week: u16,
-    total_score: u8,
-}
+use strictly_data::{Category, Dance, Row};
 
 #[derive(Debug, Deserialize)]
 pub(crate) struct UltimateRow {
@@ -18,8 +12,36 @@ pub(crate) struct UltimateRow {
     series: String,
     #[serde(rename = "Week")]
     week: String,
+    #[serde(rename = "Couple")]
+    couple: String,
+    #[serde(rename = "Dance")]
+    dance: String,
     #[serde(rename = "Total")]
     total: String,
+    #[serde(rename = "Type")]
+    dance_type: String,
+}
+
+// The ultimate dataset's types are Ballroom, Latin, Speciality and Fusion. We
+// call the specialities freestyle, and classify fusions by their styles.
+fn ultimate_category(dance_type: &str, dance: Dance) -> Option<Category> {
+    match dance_type {
+        "Ballroom" => Some(Category::Ballroom),
+        "Latin" => Some(Category::Latin),
+        "Speciality" => Some(Category::Freestyle),
+        "Fusion" => Some(dance.category()),
+        // A few rows have a score in place of the type.
+        _ => None,
+    }
+}
+
+// Couples are named by first names, e.g. "Natasha & Brendan".
+fn couple_key(series: u16, week: &str, couple: &str, dance: Dance) -> String {
+    format!("Series {} Week {} {} {}", series, week, couple, dance)
+}
+
+fn first_name(name: &str) -> &str {
+    name.split_whitespace().next().unwrap_or_default()
 }
 
 fn main() -> Result<(), Box<dyn Error>> {
@@ -29,28 +51,54 @@ fn main() -> Result<(), Box<dyn Error>> {
     let csv_file = top_dir.join("output.csv");
     println!("Parsing {}", csv_file.display());
     let mut my_scores = HashMap::<String, Vec<u8>>::new();
+    let mut my_types = HashMap::<String, Category>::new();
     let f = File::open(csv_file)?;
     let reader = BufReader::new(f);
     let mut rdr = csv::Reader::from_reader(reader);
     for result in rdr.deserialize() {
         let record: Row = result?;
         let key = format!("Series {} Week {}", record.series, record.week);
-        let entry = my_scores.entry(key).or_insert_with(Vec::new);
+        let entry = my_scores.entry(key).or_default();
         entry.push(record.total_score);
+        let couple = format!(
+            "{} & {}",
+            first_name(&record.celebrity),
+            first_name(&record.professional)
+        );
+        let key = couple_key(
+            record.series,
+            &record.week.to_string(),
+            &couple,
+            record.dance,
+        );
+        // Older output files have no dance_type column.
+        let dance_type = record.dance_type.unwrap_or_else(|| record.dance.category());
+        my_types.insert(key, dance_type);
     }
 
     let csv_file = top_dir.join("ultimate/SCD_Series18.csv");
     println!("Parsing {}", csv_file.display());
     let mut us_scores = HashMap::<String, Vec<u8>>::new();
+    let mut type_mismatches = 0;
     let f = File::open(csv_file)?;
     let reader = BufReader::new(f);
     let mut rdr = csv::Reader::from_reader(reader);
     for result in rdr.deserialize() {
         let record: UltimateRow = result?;
+        if let (Ok(series), Ok(dance)) = (record.series.parse(), record.dance.trim().parse()) {
+            let key = couple_key(series, &record.week, &record.couple, dance);
+            let us_type = ultimate_category(&record.dance_type, dance);
+            if let (Some(&my_type), Some(us_type)) = (my_types.get(&key), us_type) {
+                if my_type != us_type {
+                    println!("{}\n{}\n{} ({})", key, my_type, us_type, record.dance_type);
+                    type_mismatches += 1;
+                }
+            }
+        }
         match record.total.parse() {
             Ok(total) => {
                 let key = format!("Series {} Week {}", record.series, record.week);
-                let entry = us_scores.entry(key).or_insert_with(Vec::new);
+                let entry = us_scores.entry(key).or_default();
                 entry.push(total);
             }
             Err(_) => {
@@ -67,5 +115,8 @@ fn main() -> Result<(), Box<dyn Error>> {
             println!("{}\n{:?}\n{:?}", key, my_score, us_score);
         }
     }
+    if type_mismatches > 0 {
+        println!("{} dances are classified differently", type_mismatches);
+    }
     Ok(())
 }
@@ -0,0 +1,120 @@
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// Where and when a series page was fetched.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub(crate) struct PageInfo {
+    pub(crate) series: u16,
+    // The Wikipedia revision ID of the page, if the page contains it.
+    pub(crate) revision: Option<u64>,
+    // Seconds since the Unix epoch.
+    pub(crate) fetched: u64,
+}
+impl PageInfo {
+    pub(crate) fn new(series: u16, page: &str) -> PageInfo {
+        let fetched = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_secs())
+            .unwrap_or(0);
+        PageInfo {
+            series,
+            revision: revision_id(page),
+            fetched,
+        }
+    }
+    // For a page saved to a file, use the time the file was last modified as
+    // the fetch time.
+    pub(crate) fn from_file(series: u16, page: &str, path: &Path) -> Result<PageInfo, io::Error> {
+        let fetched = fs::metadata(path)?
+            .modified()?
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_secs())
+            .unwrap_or(0);
+        Ok(PageInfo {
+            series,
+            revision: revision_id(page),
+            fetched,
+        })
+    }
+}
+
+pub(crate) fn revision_id(page: &str) -> Option<u64> {
+    // MediaWiki includes the revision in the page's script configuration:
+    // "wgRevisionId":1034212036
+    const REVISION_ID: &str = "\"wgRevisionId\":";
+    let (_, after) = page.split_once(REVISION_ID)?;
+    let digits = after
+        .trim_start()
+        .split(|c: char| !c.is_ascii_digit())
+        .next()?;
+    digits.parse().ok()
+}
+
+// A directory of series pages, each stored as `series_N.html` with its
+// `PageInfo` in `series_N.csv`.
+#[derive(Debug)]
+pub(crate) struct PageCache {
+    dir: PathBuf,
+}
+impl PageCache {
+    pub(crate) fn new(dir: &Path) -> Result<PageCache, io::Error> {
+        fs::create_dir_all(dir)?;
+        Ok(PageCache {
+            dir: dir.to_owned(),
+        })
+    }
+    fn page_path(&self, series: u16) -> PathBuf {
+        self.dir.join(format!("series_{}.html", series))
+    }
+    fn info_path(&self, series: u16) -> PathBuf {
+        self.dir.join(format!("series_{}.csv", series))
+    }
+    pub(crate) fn get(&self, series: u16) -> Result<Option<(PageInfo, String)>, Box<dyn Error>> {
+        let page_path = self.page_path(series);
+        let info_path = self.info_path(series);
+        if !page_path.exists() || !info_path.exists() {
+            return Ok(None);
+        }
+        let mut rdr = csv::Reader::from_path(info_path)?;
+        let info = match rdr.deserialize().next() {
+            Some(info) => info?,
+            None => return Ok(None),
+        };
+        let page = fs::read_to_string(page_path)?;
+        Ok(Some((info, page)))
+    }
+    pub(crate) fn put(&self, info: &PageInfo, page: &str) -> Result<(), Box<dyn Error>> {
+        fs::write(self.page_path(info.series), page)?;
+        let mut wtr = csv::Writer::from_path(self.info_path(info.series))?;
+        wtr.serialize(info)?;
+        wtr.flush()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::error::Error;
+
+    use super::{revision_id, PageCache, PageInfo};
+
+    #[test]
+    fn test_cache_round_trip() -> Result<(), Box<dyn Error>> {
+        let page = r#"<script>RLCONF={"wgRevisionId":1034212036,"wgArticleId":2917};</script>"#;
+        assert_eq!(revision_id(page), Some(1034212036));
+
+        let dir = tempfile::tempdir()?;
+        let cache = PageCache::new(&dir.path().join("cache"))?;
+        assert!(cache.get(7)?.is_none());
+        cache.put(&PageInfo::new(7, page), page)?;
+        let (info, cached_page) = cache.get(7)?.expect("page is cached");
+        assert_eq!(info.series, 7);
+        assert_eq!(info.revision, Some(1034212036));
+        assert_eq!(cached_page, page);
+        Ok(())
+    }
+}
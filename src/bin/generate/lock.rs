@@ -0,0 +1,76 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::io;
+use std::path::Path;
+
+#[derive(Debug, Deserialize, Serialize)]
+struct LockedPage {
+    series: u16,
+    revision: u64,
+}
+
+// The Wikipedia revision to extract for each series, so that the output can be
+// reproduced after the pages are edited. Stored as CSV with one row per series.
+#[derive(Debug, Default)]
+pub(crate) struct RevisionLock {
+    revisions: BTreeMap<u16, u64>,
+}
+impl RevisionLock {
+    // Returns `None` if there is no lockfile.
+    pub(crate) fn load(path: &Path) -> Result<Option<RevisionLock>, Box<dyn Error>> {
+        let mut rdr = match csv::Reader::from_path(path) {
+            Ok(rdr) => rdr,
+            Err(error) => match error.kind() {
+                csv::ErrorKind::Io(io_error) if io_error.kind() == io::ErrorKind::NotFound => {
+                    return Ok(None);
+                }
+                _ => return Err(error.into()),
+            },
+        };
+        let mut lock = RevisionLock::default();
+        for page in rdr.deserialize() {
+            let page: LockedPage = page?;
+            lock.revisions.insert(page.series, page.revision);
+        }
+        Ok(Some(lock))
+    }
+    pub(crate) fn save(&self, path: &Path) -> Result<(), Box<dyn Error>> {
+        let mut wtr = csv::Writer::from_path(path)?;
+        for (&series, &revision) in &self.revisions {
+            wtr.serialize(LockedPage { series, revision })?;
+        }
+        wtr.flush()?;
+        Ok(())
+    }
+    pub(crate) fn get(&self, series: u16) -> Option<u64> {
+        self.revisions.get(&series).copied()
+    }
+    pub(crate) fn set(&mut self, series: u16, revision: u64) {
+        self.revisions.insert(series, revision);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::error::Error;
+
+    use super::RevisionLock;
+
+    #[test]
+    fn test_lock_round_trip() -> Result<(), Box<dyn Error>> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("revisions.lock");
+        assert!(RevisionLock::load(&path)?.is_none());
+        let mut lock = RevisionLock::default();
+        lock.set(18, 1002216573);
+        lock.set(2, 996003529);
+        lock.save(&path)?;
+        let contents = std::fs::read_to_string(&path)?;
+        let loaded = RevisionLock::load(&path)?.expect("lockfile exists");
+        assert_eq!(contents, "series,revision\n2,996003529\n18,1002216573\n");
+        assert_eq!(loaded.get(18), Some(1002216573));
+        assert_eq!(loaded.get(3), None);
+        Ok(())
+    }
+}
@@ -1,26 +1,467 @@
-mod extract;
+mod cache;
+mod lock;
+mod output;
+mod parquet_file;
+mod sqlite;
 
+use clap::Parser;
+use std::collections::BTreeMap;
 use std::error::Error;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use strictly_data::{extract_series, Aliases, ExtractError, ExtractedSeries, ProRegistry};
 
-use extract::extract_rows;
+use cache::{revision_id, PageCache, PageInfo};
+use lock::RevisionLock;
+use output::{row_writer, Format, RowWriter};
 
-fn fetch_page(series: u16) -> Result<String, reqwest::Error> {
-    let url = format!(
-        "https://en.wikipedia.org/wiki/Strictly_Come_Dancing_(series_{})",
-        series
-    );
-    reqwest::blocking::Client::new().get(url).send()?.text()
+fn page_url(series: u16, revision: Option<u64>) -> String {
+    match revision {
+        None => format!(
+            "https://en.wikipedia.org/wiki/Strictly_Come_Dancing_(series_{})",
+            series
+        ),
+        Some(revision) => format!(
+            "https://en.wikipedia.org/w/index.php?title=Strictly_Come_Dancing_(series_{})&oldid={}",
+            series, revision
+        ),
+    }
+}
+
+fn fetch_page(series: u16, revision: Option<u64>) -> Result<Option<String>, reqwest::Error> {
+    let response = reqwest::blocking::Client::new()
+        .get(page_url(series, revision))
+        .send()?;
+    // There is no page for a series that has not been announced yet.
+    if response.status() == reqwest::StatusCode::NOT_FOUND {
+        return Ok(None);
+    }
+    Ok(Some(response.error_for_status()?.text()?))
+}
+
+fn load_page(
+    cache: &PageCache,
+    series: u16,
+    refresh: bool,
+    revision: Option<u64>,
+) -> Result<Option<(PageInfo, String)>, Box<dyn Error>> {
+    // Use the cached copy of the page unless asked to refresh it, or it is
+    // not the revision we want.
+    if !refresh {
+        if let Some((info, page)) = cache.get(series)? {
+            if revision.is_none() || info.revision == revision {
+                return Ok(Some((info, page)));
+            }
+        }
+    }
+    let page = match fetch_page(series, revision)? {
+        Some(page) => page,
+        None => return Ok(None),
+    };
+    let info = PageInfo::new(series, &page);
+    cache.put(&info, &page)?;
+    Ok(Some((info, page)))
+}
+
+// Where series pages are read from.
+enum Source {
+    // Saved pages named `series_N.html`.
+    Dir(PathBuf),
+    Cache {
+        cache: PageCache,
+        offline: bool,
+        refresh: bool,
+        // With a lockfile, only the locked revision of each series is used.
+        lock: Option<RevisionLock>,
+    },
+}
+impl Source {
+    // Returns `None` if there is no page for the series.
+    fn page(&self, series: u16) -> Result<Option<(PageInfo, String)>, Box<dyn Error>> {
+        match self {
+            Source::Dir(dir) => {
+                let path = dir.join(format!("series_{}.html", series));
+                match fs::read_to_string(&path) {
+                    Ok(page) => Ok(Some((PageInfo::from_file(series, &page, &path)?, page))),
+                    Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(None),
+                    Err(error) => Err(error.into()),
+                }
+            }
+            Source::Cache {
+                cache,
+                offline,
+                refresh,
+                lock,
+            } => {
+                let revision = match lock {
+                    Some(lock) => match lock.get(series) {
+                        Some(revision) => Some(revision),
+                        None => return Ok(None),
+                    },
+                    None => None,
+                };
+                if *offline {
+                    Ok(cache
+                        .get(series)?
+                        .filter(|(info, _)| revision.is_none() || info.revision == revision))
+                } else {
+                    load_page(cache, series, *refresh, revision)
+                }
+            }
+        }
+    }
+}
+
+fn series_in_title(page: &str) -> Option<u16> {
+    // <title>Strictly Come Dancing (series 5) - Wikipedia</title>
+    let (_, title) = page.split_once("<title>")?;
+    let (title, _) = title.split_once("</title>")?;
+    let (_, series) = title.split_once("(series ")?;
+    series.split(')').next()?.trim().parse().ok()
+}
+
+// A range of series given as `5`, `5..12`, `5..=12` or `5..`. Without an
+// upper bound the range runs to the latest series.
+#[derive(Debug, Clone, PartialEq)]
+struct SeriesRange {
+    first: u16,
+    last: Option<u16>,
+}
+impl FromStr for SeriesRange {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parse = |n: &str| {
+            n.trim()
+                .parse::<u16>()
+                .map_err(|_| format!("bad series {:?}", n))
+        };
+        let (first, last) = if let Some((first, last)) = s.split_once("..=") {
+            (parse(first)?, Some(parse(last)?))
+        } else if let Some((first, last)) = s.split_once("..") {
+            if last.trim().is_empty() {
+                (parse(first)?, None)
+            } else {
+                (parse(first)?, Some(parse(last)?.saturating_sub(1)))
+            }
+        } else {
+            let series = parse(s)?;
+            (series, Some(series))
+        };
+        if first == 0 || last.is_some_and(|last| last < first) {
+            return Err(format!("empty series range {:?}", s));
+        }
+        Ok(SeriesRange { first, last })
+    }
+}
+
+#[derive(Debug, Parser)]
+#[command(
+    name = "generate",
+    about = "Extract Strictly Come Dancing (UK) scores from the show's Wikipedia pages."
+)]
+struct Opt {
+    /// Series to extract, e.g. `5`, `5..12`, `5..=12` or `5..` [default: all]
+    #[arg(long)]
+    series: Option<SeriesRange>,
+    /// Write rows to this file instead of stdout
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+    /// Output format: csv, json (an array of rows), json-nested (rows by series and week), jsonl, sqlite or parquet
+    #[arg(long, default_value = "csv")]
+    format: Format,
+    /// Directory where fetched pages are cached [default: `cache` in the source directory]
+    #[arg(long)]
+    cache_dir: Option<PathBuf>,
+    /// Use cached pages only, failing if a series in --series is not in the cache. Without a last series, the first series not in the cache ends the run
+    #[arg(long, conflicts_with = "refresh")]
+    offline: bool,
+    /// Fetch pages again even if they are cached, e.g. to update a series in progress
+    #[arg(long)]
+    refresh: bool,
+    /// Stop at the first row that cannot be extracted, instead of skipping it
+    #[arg(long)]
+    strict: bool,
+    /// Read a single saved page, detecting its series from the page title
+    #[arg(long, conflicts_with = "input_dir")]
+    input: Option<PathBuf>,
+    /// Read saved pages named `series_N.html` from this directory
+    #[arg(long)]
+    input_dir: Option<PathBuf>,
+    /// Lockfile of the page revision to use for each series [default: `revisions.lock` in the source directory]
+    #[arg(long)]
+    lock: Option<PathBuf>,
+    /// Fetch the current pages and record their revisions in the lockfile
+    #[arg(long, conflicts_with_all = ["offline", "input", "input_dir"])]
+    update_lock: bool,
+    /// Add columns for the source of each row: page URL, revision, fetch time, section and table row
+    #[arg(long)]
+    provenance: bool,
+    /// CSV file of dance and name corrections, added to the built-in ones in aliases.csv
+    #[arg(long)]
+    aliases: Option<PathBuf>,
+    /// Also write the couples in each series to this CSV file
+    #[arg(long)]
+    couples: Option<PathBuf>,
+    /// Registry of the professionals in each series, used to give each one the same name in every series [default: `professionals.csv` in the source directory]
+    #[arg(long)]
+    registry: Option<PathBuf>,
+    /// Record the professionals in the extracted series in the registry
+    #[arg(long)]
+    update_registry: bool,
+}
+
+// Where the rows and couples of each series are written.
+struct Outputs {
+    rows: Box<dyn RowWriter>,
+    couples: Option<csv::Writer<fs::File>>,
+}
+
+fn extract_page(
+    info: &PageInfo,
+    page: &str,
+    opt: &Opt,
+    aliases: &Aliases,
+    warnings: &mut Vec<ExtractError>,
+) -> Result<ExtractedSeries, Box<dyn Error>> {
+    let series = info.series;
+    let mut extracted = extract_series(series, page, aliases, opt.strict)?;
+    warnings.append(&mut extracted.warnings);
+    if extracted.rows.iter().any(|row| row.partial) {
+        eprintln!(
+            "Series {} is still in progress: its rows are marked partial",
+            series
+        );
+    }
+    Ok(extracted)
+}
+
+fn write_series(
+    outputs: &mut Outputs,
+    info: &PageInfo,
+    mut extracted: ExtractedSeries,
+    opt: &Opt,
+    registry: &ProRegistry,
+) -> Result<(), Box<dyn Error>> {
+    registry.apply(&mut extracted.rows);
+    let url = page_url(info.series, info.revision);
+    for row in &mut extracted.rows {
+        if opt.provenance {
+            row.add_provenance(&url, info.revision, info.fetched);
+        }
+        outputs.rows.write(row)?;
+    }
+    if let Some(couples_writer) = &mut outputs.couples {
+        registry.apply_to_couples(&mut extracted.couples);
+        for couple in extracted.couples {
+            couples_writer.serialize(couple)?;
+        }
+    }
+    Ok(())
+}
+
+// List the couples that could not be found in the Couples table, once for
+// each series, with the celebrities they could be. Their rows are written with
+// the short names from the Week tables, and can be fixed with a moniker alias.
+fn report_unresolved_couples(warnings: &[ExtractError]) {
+    let mut couples: BTreeMap<u16, BTreeMap<&str, &[String]>> = BTreeMap::new();
+    for warning in warnings {
+        let (location, couple, candidates) = match warning {
+            ExtractError::UnresolvedCouple(location, couple) => (location, couple, &[][..]),
+            ExtractError::AmbiguousCouple(location, couple, candidates) => {
+                (location, couple, &candidates[..])
+            }
+            _ => continue,
+        };
+        couples
+            .entry(location.series)
+            .or_default()
+            .insert(couple, candidates);
+    }
+    if couples.is_empty() {
+        return;
+    }
+    eprintln!("Unresolved couples, written with their short names:");
+    for (series, couples) in couples {
+        eprintln!("  series {}:", series);
+        for (couple, candidates) in couples {
+            if candidates.is_empty() {
+                eprintln!("    {:?}: not in the Couples table", couple);
+            } else {
+                eprintln!("    {:?}: could be {}", couple, candidates.join(" or "));
+            }
+        }
+    }
 }
 
 fn main() -> Result<(), Box<dyn Error>> {
-    const LATEST_SERIES: u16 = 20;
-    let mut wtr = csv::Writer::from_writer(std::io::stdout());
-    for series in 1..=LATEST_SERIES {
-        let page = fetch_page(series)?;
-        for row in extract_rows(series, &page)? {
-            wtr.serialize(row)?;
+    let opt = Opt::parse();
+    let aliases = match &opt.aliases {
+        Some(path) => Aliases::load(path)?,
+        None => Aliases::default(),
+    };
+    let registry_path = opt
+        .registry
+        .clone()
+        .unwrap_or_else(|| Path::new(env!("CARGO_MANIFEST_DIR")).join("professionals.csv"));
+    let mut registry = ProRegistry::load(&registry_path)?;
+    let mut warnings = Vec::new();
+    // Every series is extracted before any is written, so that the registry
+    // has the latest name of each professional when the rows are written.
+    let mut extracted_series = Vec::new();
+    if let Some(file) = &opt.input {
+        let page = fs::read_to_string(file)?;
+        let series = series_in_title(&page)
+            .ok_or_else(|| format!("{}: no series found in page title", file.display()))?;
+        eprintln!("{}: series {}", file.display(), series);
+        let info = PageInfo::from_file(series, &page, file)?;
+        let extracted = extract_page(&info, &page, &opt, &aliases, &mut warnings)?;
+        extracted_series.push((info, extracted));
+    } else {
+        let lock_path = opt
+            .lock
+            .clone()
+            .unwrap_or_else(|| Path::new(env!("CARGO_MANIFEST_DIR")).join("revisions.lock"));
+        let mut lock = RevisionLock::load(&lock_path)?;
+        // When updating the lockfile, the current pages are used and their
+        // revisions recorded, keeping any other series already in the lockfile.
+        let mut new_lock = None;
+        if opt.update_lock {
+            new_lock = Some(lock.take().unwrap_or_default());
+        }
+        let source = match &opt.input_dir {
+            Some(dir) => Source::Dir(dir.clone()),
+            None => {
+                let cache_dir = opt
+                    .cache_dir
+                    .clone()
+                    .unwrap_or_else(|| Path::new(env!("CARGO_MANIFEST_DIR")).join("cache"));
+                Source::Cache {
+                    cache: PageCache::new(&cache_dir)?,
+                    offline: opt.offline,
+                    refresh: opt.refresh || opt.update_lock,
+                    lock,
+                }
+            }
+        };
+        let range = opt.series.clone().unwrap_or(SeriesRange {
+            first: 1,
+            last: None,
+        });
+        for series in range.first.. {
+            let (info, page) = match source.page(series)? {
+                Some(page) => page,
+                // Without a last series, the first missing page is the end.
+                None if range.last.is_none() && series > range.first => {
+                    // Offline, a gap in the cache looks the same as the end.
+                    if opt.offline {
+                        eprintln!(
+                            "Series {} is not in the cache: stopping after series {}",
+                            series,
+                            series - 1
+                        );
+                    }
+                    break;
+                }
+                None => return Err(format!("no page for series {}", series).into()),
+            };
+            if let Some(new_lock) = &mut new_lock {
+                let revision = revision_id(&page)
+                    .ok_or_else(|| format!("no revision ID in page for series {}", series))?;
+                new_lock.set(series, revision);
+            }
+            let extracted = extract_page(&info, &page, &opt, &aliases, &mut warnings)?;
+            extracted_series.push((info, extracted));
+            if Some(series) == range.last {
+                break;
+            }
+        }
+        if let Some(new_lock) = new_lock {
+            new_lock.save(&lock_path)?;
+            eprintln!("Updated {}", lock_path.display());
         }
     }
-    wtr.flush()?;
+    if opt.update_registry {
+        for (info, extracted) in &extracted_series {
+            registry.update(info.series, &extracted.rows);
+        }
+        registry.save(&registry_path)?;
+        eprintln!("Updated {}", registry_path.display());
+    }
+    // The outputs are only created once every series has been extracted, so
+    // that a failure leaves any previous output in place.
+    let mut outputs = Outputs {
+        rows: row_writer(opt.format, opt.output.as_deref())?,
+        couples: match &opt.couples {
+            Some(path) => Some(csv::Writer::from_path(path)?),
+            None => None,
+        },
+    };
+    for (info, extracted) in extracted_series {
+        write_series(&mut outputs, &info, extracted, &opt, &registry)?;
+    }
+    outputs.rows.finish()?;
+    if let Some(mut couples_writer) = outputs.couples {
+        couples_writer.flush()?;
+    }
+    // Rows with an unknown result, or marks that do not match the judges,
+    // are written without them.
+    let (incomplete, skipped): (Vec<_>, Vec<_>) = warnings
+        .iter()
+        .filter(|warning| {
+            !matches!(
+                warning,
+                ExtractError::UnresolvedCouple(_, _) | ExtractError::AmbiguousCouple(_, _, _)
+            )
+        })
+        .partition(|warning| {
+            matches!(
+                warning,
+                ExtractError::UnknownResult(_, _) | ExtractError::UnmatchedMarks(_, _, _)
+            )
+        });
+    if !skipped.is_empty() {
+        eprintln!("Skipped {} rows or tables:", skipped.len());
+        for warning in skipped {
+            eprintln!("  {}", warning);
+        }
+    }
+    if !incomplete.is_empty() {
+        eprintln!("Wrote {} rows without some values:", incomplete.len());
+        for warning in incomplete {
+            eprintln!("  {}", warning);
+        }
+    }
+    report_unresolved_couples(&warnings);
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{series_in_title, SeriesRange};
+
+    #[test]
+    fn test_series_in_title() {
+        let page = "<html><head><meta charset=\"UTF-8\"/>\n\
+            <title>Strictly Come Dancing (series 14) - Wikipedia</title>";
+        assert_eq!(series_in_title(page), Some(14));
+        assert_eq!(
+            series_in_title("<title>Strictly Come Dancing</title>"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_series_range() {
+        let range = |first, last| SeriesRange { first, last };
+        assert_eq!("7".parse(), Ok(range(7, Some(7))));
+        assert_eq!("5..12".parse(), Ok(range(5, Some(11))));
+        assert_eq!("5..=12".parse(), Ok(range(5, Some(12))));
+        assert_eq!("18..".parse(), Ok(range(18, None)));
+        assert!("12..=5".parse::<SeriesRange>().is_err());
+        assert!("0..3".parse::<SeriesRange>().is_err());
+        assert!("five".parse::<SeriesRange>().is_err());
+    }
+}
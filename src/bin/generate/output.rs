@@ -0,0 +1,247 @@
+use std::error::Error;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use std::str::FromStr;
+
+use serde::Serialize;
+
+use crate::parquet_file::ParquetWriter;
+use crate::sqlite::SqliteWriter;
+use strictly_data::{Provenance, Row};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Format {
+    Csv,
+    // A single JSON array of rows.
+    Json,
+    // A JSON array of series, each with an array of weeks containing the rows.
+    JsonNested,
+    // One JSON object per line.
+    Jsonl,
+    // A SQLite database, see `sqlite.rs`.
+    Sqlite,
+    // An Apache Parquet file, see `parquet_file.rs`.
+    Parquet,
+}
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "csv" => Ok(Format::Csv),
+            "json" => Ok(Format::Json),
+            "json-nested" => Ok(Format::JsonNested),
+            "jsonl" => Ok(Format::Jsonl),
+            "sqlite" => Ok(Format::Sqlite),
+            "parquet" => Ok(Format::Parquet),
+            _ => Err(format!(
+                "unknown format {:?} (expected csv, json, json-nested, jsonl, sqlite or parquet)",
+                s
+            )),
+        }
+    }
+}
+
+pub(crate) trait RowWriter {
+    fn write(&mut self, row: &Row) -> Result<(), Box<dyn Error>>;
+    fn finish(&mut self) -> Result<(), Box<dyn Error>>;
+}
+
+// Write to the output file, or to stdout if there is none.
+fn open(output: Option<&Path>) -> Result<Box<dyn Write>, io::Error> {
+    Ok(match output {
+        Some(path) => Box::new(BufWriter::new(File::create(path)?)),
+        None => Box::new(io::stdout()),
+    })
+}
+
+pub(crate) fn row_writer(
+    format: Format,
+    output: Option<&Path>,
+) -> Result<Box<dyn RowWriter>, Box<dyn Error>> {
+    Ok(match format {
+        Format::Csv => Box::new(CsvWriter {
+            wtr: csv::Writer::from_writer(open(output)?),
+        }),
+        Format::Json => Box::new(JsonWriter {
+            out: open(output)?,
+            rows: 0,
+        }),
+        Format::JsonNested => Box::new(NestedJsonWriter {
+            out: open(output)?,
+            week: None,
+        }),
+        Format::Jsonl => Box::new(JsonlWriter { out: open(output)? }),
+        Format::Sqlite => {
+            let path = output.ok_or("the sqlite format needs an output file")?;
+            Box::new(SqliteWriter::create(path)?)
+        }
+        Format::Parquet => {
+            let path = output.ok_or("the parquet format needs an output file")?;
+            Box::new(ParquetWriter::create(path))
+        }
+    })
+}
+
+// Text columns are null rather than empty, as in the JSON output.
+pub(crate) fn non_empty(value: &str) -> Option<&str> {
+    if value.is_empty() {
+        None
+    } else {
+        Some(value)
+    }
+}
+
+// A row with its provenance columns, if it has any, as one JSON object.
+#[derive(Serialize)]
+struct JsonRow<'a> {
+    #[serde(flatten)]
+    row: &'a Row,
+    #[serde(flatten)]
+    provenance: &'a Option<Provenance>,
+}
+impl<'a> From<&'a Row> for JsonRow<'a> {
+    fn from(row: &'a Row) -> Self {
+        JsonRow {
+            row,
+            provenance: &row.provenance,
+        }
+    }
+}
+
+struct CsvWriter {
+    wtr: csv::Writer<Box<dyn Write>>,
+}
+impl RowWriter for CsvWriter {
+    fn write(&mut self, row: &Row) -> Result<(), Box<dyn Error>> {
+        // A record can be a tuple of structs, which the csv crate writes one
+        // after the other, but not a struct with flattened fields.
+        match &row.provenance {
+            Some(provenance) => self.wtr.serialize((row, provenance))?,
+            None => self.wtr.serialize(row)?,
+        }
+        Ok(())
+    }
+    fn finish(&mut self) -> Result<(), Box<dyn Error>> {
+        self.wtr.flush()?;
+        Ok(())
+    }
+}
+
+// Rows are streamed rather than collected, so the array brackets and
+// separators are written by hand.
+struct JsonWriter {
+    out: Box<dyn Write>,
+    rows: usize,
+}
+impl RowWriter for JsonWriter {
+    fn write(&mut self, row: &Row) -> Result<(), Box<dyn Error>> {
+        self.out
+            .write_all(if self.rows == 0 { b"[\n" } else { b",\n" })?;
+        serde_json::to_writer(&mut self.out, &JsonRow::from(row))?;
+        self.rows += 1;
+        Ok(())
+    }
+    fn finish(&mut self) -> Result<(), Box<dyn Error>> {
+        self.out
+            .write_all(if self.rows == 0 { b"[]\n" } else { b"\n]\n" })?;
+        self.out.flush()?;
+        Ok(())
+    }
+}
+
+// Rows arrive in page order, so a new series or week closes the previous one.
+struct NestedJsonWriter {
+    out: Box<dyn Write>,
+    // The series and week of the previous row.
+    week: Option<(u16, u16)>,
+}
+impl RowWriter for NestedJsonWriter {
+    fn write(&mut self, row: &Row) -> Result<(), Box<dyn Error>> {
+        let (series, week) = (row.series, row.week);
+        let separator = match self.week {
+            None => format!(
+                "[\n{{\"series\":{},\"weeks\":[\n{{\"week\":{},\"rows\":[\n",
+                series, week
+            ),
+            Some(previous) if previous == (series, week) => ",\n".to_owned(),
+            Some((previous_series, _)) if previous_series == series => {
+                format!("\n]}},\n{{\"week\":{},\"rows\":[\n", week)
+            }
+            Some(_) => format!(
+                "\n]}}\n]}},\n{{\"series\":{},\"weeks\":[\n{{\"week\":{},\"rows\":[\n",
+                series, week
+            ),
+        };
+        self.out.write_all(separator.as_bytes())?;
+        serde_json::to_writer(&mut self.out, &JsonRow::from(row))?;
+        self.week = Some((series, week));
+        Ok(())
+    }
+    fn finish(&mut self) -> Result<(), Box<dyn Error>> {
+        self.out.write_all(match self.week {
+            None => b"[]\n",
+            Some(_) => b"\n]}\n]}\n]\n",
+        })?;
+        self.out.flush()?;
+        Ok(())
+    }
+}
+
+struct JsonlWriter {
+    out: Box<dyn Write>,
+}
+impl RowWriter for JsonlWriter {
+    fn write(&mut self, row: &Row) -> Result<(), Box<dyn Error>> {
+        serde_json::to_writer(&mut self.out, &JsonRow::from(row))?;
+        self.out.write_all(b"\n")?;
+        Ok(())
+    }
+    fn finish(&mut self) -> Result<(), Box<dyn Error>> {
+        self.out.flush()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::error::Error;
+    use std::fs::File;
+
+    use super::{row_writer, Format};
+    use strictly_data::{extract_rows, Aliases};
+
+    #[test]
+    fn test_nested_json() -> Result<(), Box<dyn Error>> {
+        let top = env!("CARGO_MANIFEST_DIR");
+        let page = std::fs::read_to_string(format!("{}/test-data/test4.html", top))?;
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("rows.json");
+
+        let mut writer = row_writer(Format::JsonNested, Some(&path))?;
+        for mut row in extract_rows(17, &page, &Aliases::default())? {
+            row.add_provenance("https://example.org/series_17", None, 1600000000);
+            writer.write(&row)?;
+        }
+        writer.finish()?;
+        let json: serde_json::Value = serde_json::from_reader(File::open(&path)?)?;
+
+        let weeks = &json[0]["weeks"];
+        assert_eq!(json[0]["series"], 17);
+        assert_eq!(weeks.as_array().map(Vec::len), Some(4));
+        assert_eq!(weeks[2]["week"], 9);
+        assert_eq!(weeks[2]["rows"].as_array().map(Vec::len), Some(2));
+        // Missing values are null rather than empty strings.
+        let row = &weeks[0]["rows"][0];
+        assert_eq!(row["celebrity"], "Kelvin");
+        assert!(row["arlene"].is_null());
+        assert!(row["note"].is_null());
+        assert!(row["guest_judge"].is_null());
+        // The provenance is in the same object as the rest of the row.
+        assert_eq!(row["source_section"], "Week_1");
+        assert_eq!(row["source_row"], 2);
+        assert!(row["source_revision"].is_null());
+        Ok(())
+    }
+}
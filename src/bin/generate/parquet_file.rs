@@ -0,0 +1,297 @@
+use arrow_array::types::Int32Type;
+use arrow_array::{
+    ArrayRef, BooleanArray, DictionaryArray, Float32Array, RecordBatch, StringArray, UInt16Array,
+    UInt64Array, UInt8Array,
+};
+use arrow_schema::{Field, Schema};
+use parquet::arrow::ArrowWriter;
+use parquet::basic::Compression;
+use parquet::file::properties::WriterProperties;
+use std::error::Error;
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use crate::output::{non_empty, RowWriter};
+use strictly_data::{Provenance, Row};
+
+// The columns of the file and their schema, added one at a time.
+#[derive(Default)]
+struct Columns {
+    fields: Vec<Field>,
+    arrays: Vec<ArrayRef>,
+}
+impl Columns {
+    fn add(&mut self, name: &str, nullable: bool, array: ArrayRef) {
+        self.fields
+            .push(Field::new(name, array.data_type().clone(), nullable));
+        self.arrays.push(array);
+    }
+}
+
+// Categorical text, such as dance names, stored as a dictionary.
+fn categories<'a>(values: impl Iterator<Item = Option<&'a str>>) -> ArrayRef {
+    Arc::new(values.collect::<DictionaryArray<Int32Type>>())
+}
+
+fn strings<'a>(values: impl Iterator<Item = Option<&'a str>>) -> ArrayRef {
+    Arc::new(values.collect::<StringArray>())
+}
+
+fn marks(values: impl Iterator<Item = Option<u8>>) -> ArrayRef {
+    Arc::new(values.collect::<UInt8Array>())
+}
+
+// Writes the rows as an Apache Parquet file. The columns are the same as the
+// CSV output, but typed, so the file is only written once all rows are known.
+pub(crate) struct ParquetWriter {
+    path: PathBuf,
+    rows: Vec<Row>,
+}
+impl ParquetWriter {
+    pub(crate) fn create(path: &Path) -> ParquetWriter {
+        ParquetWriter {
+            path: path.to_owned(),
+            rows: Vec::new(),
+        }
+    }
+    fn record_batch(&self) -> Result<RecordBatch, Box<dyn Error>> {
+        let rows = &self.rows;
+        let mut columns = Columns::default();
+        columns.add(
+            "series",
+            false,
+            Arc::new(UInt16Array::from_iter_values(rows.iter().map(|r| r.series))),
+        );
+        columns.add(
+            "week",
+            false,
+            Arc::new(UInt16Array::from_iter_values(rows.iter().map(|r| r.week))),
+        );
+        columns.add(
+            "celebrity",
+            false,
+            strings(rows.iter().map(|r| Some(r.celebrity.as_str()))),
+        );
+        columns.add(
+            "professional",
+            false,
+            strings(rows.iter().map(|r| Some(r.professional.as_str()))),
+        );
+        let dances: Vec<String> = rows.iter().map(|r| r.dance.to_string()).collect();
+        columns.add(
+            "dance",
+            false,
+            categories(dances.iter().map(|dance| Some(dance.as_str()))),
+        );
+        columns.add(
+            "total_score",
+            false,
+            marks(rows.iter().map(|r| Some(r.total_score))),
+        );
+        columns.add(
+            "score_count",
+            false,
+            marks(rows.iter().map(|r| Some(r.score_count))),
+        );
+        columns.add(
+            "avg_score",
+            false,
+            Arc::new(Float32Array::from_iter_values(
+                rows.iter().map(|r| r.avg_score),
+            )),
+        );
+        columns.add("craig", true, marks(rows.iter().map(|r| r.craig)));
+        columns.add("arlene", true, marks(rows.iter().map(|r| r.arlene)));
+        columns.add("len", true, marks(rows.iter().map(|r| r.len)));
+        columns.add("bruno", true, marks(rows.iter().map(|r| r.bruno)));
+        columns.add("alesha", true, marks(rows.iter().map(|r| r.alesha)));
+        columns.add("darcey", true, marks(rows.iter().map(|r| r.darcey)));
+        columns.add("shirley", true, marks(rows.iter().map(|r| r.shirley)));
+        columns.add("motsi", true, marks(rows.iter().map(|r| r.motsi)));
+        columns.add("anton", true, marks(rows.iter().map(|r| r.anton)));
+        columns.add("guest", true, marks(rows.iter().map(|r| r.guest)));
+        columns.add(
+            "guest_judge",
+            true,
+            strings(rows.iter().map(|r| non_empty(&r.guest_judge))),
+        );
+        let results: Vec<Option<String>> = rows
+            .iter()
+            .map(|r| r.result.map(|result| result.to_string()))
+            .collect();
+        columns.add(
+            "result",
+            true,
+            categories(results.iter().map(Option::as_deref)),
+        );
+        columns.add(
+            "song",
+            true,
+            strings(rows.iter().map(|r| non_empty(&r.song))),
+        );
+        columns.add(
+            "artist",
+            true,
+            strings(rows.iter().map(|r| non_empty(&r.artist))),
+        );
+        columns.add("show", true, marks(rows.iter().map(|r| r.show)));
+        columns.add(
+            "show_title",
+            true,
+            strings(rows.iter().map(|r| non_empty(&r.show_title))),
+        );
+        columns.add(
+            "running_order",
+            false,
+            marks(rows.iter().map(|r| Some(r.running_order))),
+        );
+        columns.add(
+            "week_theme",
+            true,
+            categories(rows.iter().map(|r| non_empty(&r.week_theme))),
+        );
+        columns.add(
+            "note",
+            true,
+            strings(rows.iter().map(|r| non_empty(&r.note))),
+        );
+        columns.add(
+            "partial",
+            false,
+            Arc::new(
+                rows.iter()
+                    .map(|r| Some(r.partial))
+                    .collect::<BooleanArray>(),
+            ),
+        );
+        let dance_types: Vec<String> = rows
+            .iter()
+            .map(|r| r.dance.category().to_string())
+            .collect();
+        columns.add(
+            "dance_type",
+            false,
+            categories(
+                dance_types
+                    .iter()
+                    .map(|dance_type| Some(dance_type.as_str())),
+            ),
+        );
+        columns.add(
+            "celebrity_id",
+            true,
+            strings(rows.iter().map(|r| r.celebrity_id.as_deref())),
+        );
+        columns.add(
+            "professional_id",
+            true,
+            strings(rows.iter().map(|r| r.professional_id.as_deref())),
+        );
+        // Provenance columns are only written if they were added to the rows.
+        let provenance: Option<Vec<&Provenance>> =
+            rows.iter().map(|r| r.provenance.as_ref()).collect();
+        if let Some(provenance) = provenance.filter(|p| !p.is_empty()) {
+            columns.add(
+                "source_url",
+                false,
+                categories(provenance.iter().map(|p| Some(p.url.as_str()))),
+            );
+            columns.add(
+                "source_revision",
+                true,
+                Arc::new(
+                    provenance
+                        .iter()
+                        .map(|p| p.revision)
+                        .collect::<UInt64Array>(),
+                ),
+            );
+            columns.add(
+                "source_fetched",
+                false,
+                Arc::new(
+                    provenance
+                        .iter()
+                        .map(|p| Some(p.fetched))
+                        .collect::<UInt64Array>(),
+                ),
+            );
+            columns.add(
+                "source_section",
+                false,
+                categories(provenance.iter().map(|p| Some(p.section.as_str()))),
+            );
+            columns.add(
+                "source_row",
+                false,
+                marks(provenance.iter().map(|p| Some(p.row))),
+            );
+        }
+        let schema = Schema::new(columns.fields);
+        Ok(RecordBatch::try_new(Arc::new(schema), columns.arrays)?)
+    }
+}
+impl RowWriter for ParquetWriter {
+    fn write(&mut self, row: &Row) -> Result<(), Box<dyn Error>> {
+        self.rows.push(row.clone());
+        Ok(())
+    }
+    fn finish(&mut self) -> Result<(), Box<dyn Error>> {
+        let batch = self.record_batch()?;
+        let properties = WriterProperties::builder()
+            .set_compression(Compression::SNAPPY)
+            .build();
+        let mut writer =
+            ArrowWriter::try_new(File::create(&self.path)?, batch.schema(), Some(properties))?;
+        writer.write(&batch)?;
+        writer.close()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow_schema::DataType;
+    use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+    use std::error::Error;
+    use std::fs::File;
+
+    use super::ParquetWriter;
+    use crate::output::RowWriter;
+    use strictly_data::{extract_rows, Aliases};
+
+    #[test]
+    fn test_parquet() -> Result<(), Box<dyn Error>> {
+        let top = env!("CARGO_MANIFEST_DIR");
+        let page = std::fs::read_to_string(format!("{}/test-data/test4.html", top))?;
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("rows.parquet");
+
+        let mut writer = ParquetWriter::create(&path);
+        for row in extract_rows(17, &page, &Aliases::default())? {
+            writer.write(&row)?;
+        }
+        writer.finish()?;
+        let reader = ParquetRecordBatchReaderBuilder::try_new(File::open(&path)?)?;
+        let rows = reader.metadata().file_metadata().num_rows();
+        let schema = reader.schema().clone();
+
+        assert_eq!(rows, 7);
+        assert_eq!(
+            schema.field_with_name("series")?.data_type(),
+            &DataType::UInt16
+        );
+        assert_eq!(
+            schema.field_with_name("avg_score")?.data_type(),
+            &DataType::Float32
+        );
+        assert!(matches!(
+            schema.field_with_name("dance")?.data_type(),
+            DataType::Dictionary(_, _)
+        ));
+        assert!(schema.field_with_name("note")?.is_nullable());
+        assert!(schema.field_with_name("source_url").is_err());
+        Ok(())
+    }
+}
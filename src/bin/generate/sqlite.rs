@@ -0,0 +1,334 @@
+use rusqlite::{params, Connection};
+use std::collections::HashMap;
+use std::error::Error;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::output::{non_empty, RowWriter};
+use strictly_data::{Dance, Row};
+
+// A Wikipedia article, or a name in a series.
+type PersonKey = (Option<u16>, String);
+
+const SCHEMA: &str = "
+CREATE TABLE series (
+    id INTEGER PRIMARY KEY,
+    partial INTEGER NOT NULL
+);
+CREATE TABLE weeks (
+    id INTEGER PRIMARY KEY,
+    series_id INTEGER NOT NULL REFERENCES series(id),
+    week INTEGER NOT NULL,
+    theme TEXT,
+    UNIQUE (series_id, week)
+);
+CREATE TABLE celebrities (
+    id INTEGER PRIMARY KEY,
+    name TEXT NOT NULL,
+    wiki_id TEXT UNIQUE
+);
+CREATE TABLE professionals (
+    id INTEGER PRIMARY KEY,
+    name TEXT NOT NULL,
+    wiki_id TEXT UNIQUE
+);
+CREATE TABLE couples (
+    id INTEGER PRIMARY KEY,
+    series_id INTEGER NOT NULL REFERENCES series(id),
+    celebrity_id INTEGER NOT NULL REFERENCES celebrities(id),
+    professional_id INTEGER NOT NULL REFERENCES professionals(id),
+    UNIQUE (series_id, celebrity_id, professional_id)
+);
+CREATE TABLE dances (
+    id INTEGER PRIMARY KEY,
+    name TEXT NOT NULL UNIQUE,
+    type TEXT NOT NULL
+);
+CREATE TABLE scores (
+    id INTEGER PRIMARY KEY,
+    week_id INTEGER NOT NULL REFERENCES weeks(id),
+    couple_id INTEGER NOT NULL REFERENCES couples(id),
+    dance_id INTEGER NOT NULL REFERENCES dances(id),
+    show INTEGER,
+    show_title TEXT,
+    running_order INTEGER NOT NULL,
+    total_score INTEGER NOT NULL,
+    score_count INTEGER NOT NULL,
+    avg_score REAL NOT NULL,
+    craig INTEGER,
+    arlene INTEGER,
+    len INTEGER,
+    bruno INTEGER,
+    alesha INTEGER,
+    darcey INTEGER,
+    shirley INTEGER,
+    motsi INTEGER,
+    anton INTEGER,
+    guest INTEGER,
+    guest_judge TEXT,
+    result TEXT,
+    song TEXT,
+    artist TEXT,
+    note TEXT
+);
+";
+
+// Writes the rows into a SQLite database, with a table for each kind of thing
+// in a row. The IDs of the rows already inserted into each table are kept so
+// that people, couples and dances are only inserted once. People are known by
+// their Wikipedia article if they have one, so someone who changed their name
+// between series is still one person. Otherwise they are known by their name
+// in a series, as people in different series can share a name.
+pub(crate) struct SqliteWriter {
+    conn: Connection,
+    series: HashMap<u16, i64>,
+    weeks: HashMap<(u16, u16), i64>,
+    celebrities: HashMap<PersonKey, i64>,
+    professionals: HashMap<PersonKey, i64>,
+    couples: HashMap<(i64, i64, i64), i64>,
+    dances: HashMap<Dance, i64>,
+}
+impl SqliteWriter {
+    pub(crate) fn create(path: &Path) -> Result<SqliteWriter, Box<dyn Error>> {
+        // Start from an empty database, as for the other formats.
+        match fs::remove_file(path) {
+            Err(error) if error.kind() != io::ErrorKind::NotFound => return Err(error.into()),
+            _ => {}
+        }
+        let conn = Connection::open(path)?;
+        conn.execute_batch("PRAGMA foreign_keys = ON;")?;
+        conn.execute_batch(SCHEMA)?;
+        // Insert everything in one transaction, committed by `finish`.
+        conn.execute_batch("BEGIN;")?;
+        Ok(SqliteWriter {
+            conn,
+            series: HashMap::new(),
+            weeks: HashMap::new(),
+            celebrities: HashMap::new(),
+            professionals: HashMap::new(),
+            couples: HashMap::new(),
+            dances: HashMap::new(),
+        })
+    }
+    fn series_id(&mut self, row: &Row) -> Result<i64, rusqlite::Error> {
+        if let Some(&id) = self.series.get(&row.series) {
+            return Ok(id);
+        }
+        self.conn.execute(
+            "INSERT INTO series (id, partial) VALUES (?1, ?2)",
+            params![row.series, row.partial],
+        )?;
+        let id = self.conn.last_insert_rowid();
+        self.series.insert(row.series, id);
+        Ok(id)
+    }
+    fn week_id(&mut self, row: &Row, series_id: i64) -> Result<i64, rusqlite::Error> {
+        if let Some(&id) = self.weeks.get(&(row.series, row.week)) {
+            return Ok(id);
+        }
+        self.conn.execute(
+            "INSERT INTO weeks (series_id, week, theme) VALUES (?1, ?2, ?3)",
+            params![series_id, row.week, non_empty(&row.week_theme)],
+        )?;
+        let id = self.conn.last_insert_rowid();
+        self.weeks.insert((row.series, row.week), id);
+        Ok(id)
+    }
+    fn person_id(
+        conn: &Connection,
+        ids: &mut HashMap<PersonKey, i64>,
+        table: &str,
+        series: u16,
+        name: &str,
+        wiki_id: Option<&str>,
+    ) -> Result<i64, rusqlite::Error> {
+        let key = match wiki_id {
+            Some(wiki_id) => (None, wiki_id.to_owned()),
+            None => (Some(series), name.to_owned()),
+        };
+        if let Some(&id) = ids.get(&key) {
+            return Ok(id);
+        }
+        conn.execute(
+            &format!("INSERT INTO {} (name, wiki_id) VALUES (?1, ?2)", table),
+            params![name, wiki_id],
+        )?;
+        let id = conn.last_insert_rowid();
+        ids.insert(key, id);
+        Ok(id)
+    }
+    fn couple_id(&mut self, row: &Row, series_id: i64) -> Result<i64, rusqlite::Error> {
+        let celebrity_id = SqliteWriter::person_id(
+            &self.conn,
+            &mut self.celebrities,
+            "celebrities",
+            row.series,
+            &row.celebrity,
+            row.celebrity_id.as_deref(),
+        )?;
+        let professional_id = SqliteWriter::person_id(
+            &self.conn,
+            &mut self.professionals,
+            "professionals",
+            row.series,
+            &row.professional,
+            row.professional_id.as_deref(),
+        )?;
+        let key = (series_id, celebrity_id, professional_id);
+        if let Some(&id) = self.couples.get(&key) {
+            return Ok(id);
+        }
+        self.conn.execute(
+            "INSERT INTO couples (series_id, celebrity_id, professional_id) VALUES (?1, ?2, ?3)",
+            params![series_id, celebrity_id, professional_id],
+        )?;
+        let id = self.conn.last_insert_rowid();
+        self.couples.insert(key, id);
+        Ok(id)
+    }
+    fn dance_id(&mut self, row: &Row) -> Result<i64, rusqlite::Error> {
+        if let Some(&id) = self.dances.get(&row.dance) {
+            return Ok(id);
+        }
+        self.conn.execute(
+            "INSERT INTO dances (name, type) VALUES (?1, ?2)",
+            params![row.dance.to_string(), row.dance.category().to_string()],
+        )?;
+        let id = self.conn.last_insert_rowid();
+        self.dances.insert(row.dance, id);
+        Ok(id)
+    }
+}
+impl RowWriter for SqliteWriter {
+    fn write(&mut self, row: &Row) -> Result<(), Box<dyn Error>> {
+        let series_id = self.series_id(row)?;
+        let week_id = self.week_id(row, series_id)?;
+        let couple_id = self.couple_id(row, series_id)?;
+        let dance_id = self.dance_id(row)?;
+        self.conn.execute(
+            "INSERT INTO scores (
+                week_id, couple_id, dance_id, show, show_title, running_order,
+                total_score, score_count, avg_score,
+                craig, arlene, len, bruno, alesha, darcey, shirley, motsi, anton, guest,
+                guest_judge, result, song, artist, note
+            ) VALUES (
+                ?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12,
+                ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20, ?21, ?22, ?23, ?24
+            )",
+            params![
+                week_id,
+                couple_id,
+                dance_id,
+                row.show,
+                non_empty(&row.show_title),
+                row.running_order,
+                row.total_score,
+                row.score_count,
+                row.avg_score,
+                row.craig,
+                row.arlene,
+                row.len,
+                row.bruno,
+                row.alesha,
+                row.darcey,
+                row.shirley,
+                row.motsi,
+                row.anton,
+                row.guest,
+                non_empty(&row.guest_judge),
+                row.result.map(|result| result.to_string()),
+                non_empty(&row.song),
+                non_empty(&row.artist),
+                non_empty(&row.note),
+            ],
+        )?;
+        Ok(())
+    }
+    fn finish(&mut self) -> Result<(), Box<dyn Error>> {
+        self.conn.execute_batch("COMMIT;")?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rusqlite::Connection;
+    use std::error::Error;
+
+    use super::SqliteWriter;
+    use crate::output::RowWriter;
+    use strictly_data::{extract_rows, Aliases};
+
+    #[test]
+    fn test_sqlite() -> Result<(), Box<dyn Error>> {
+        let top = env!("CARGO_MANIFEST_DIR");
+        let page = std::fs::read_to_string(format!("{}/test-data/test4.html", top))?;
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("rows.sqlite");
+
+        let mut writer = SqliteWriter::create(&path)?;
+        for row in extract_rows(17, &page, &Aliases::default())? {
+            writer.write(&row)?;
+        }
+        writer.finish()?;
+        let conn = Connection::open(&path)?;
+        let count = |sql: &str| conn.query_row(sql, [], |row| row.get::<_, i64>(0));
+        assert_eq!(count("SELECT COUNT(*) FROM scores")?, 7);
+        assert_eq!(count("SELECT COUNT(*) FROM weeks")?, 4);
+        assert_eq!(count("SELECT COUNT(*) FROM couples")?, 2);
+        assert_eq!(count("SELECT COUNT(*) FROM dances")?, 7);
+        assert_eq!(
+            count("SELECT COUNT(*) FROM dances WHERE type = 'Freestyle'")?,
+            1
+        );
+        let kelvin_total = count(
+            "SELECT SUM(total_score) FROM scores
+            JOIN couples ON couples.id = scores.couple_id
+            JOIN celebrities ON celebrities.id = couples.celebrity_id
+            WHERE celebrities.name = 'Kelvin'",
+        )?;
+        assert!(kelvin_total > 0);
+        Ok(())
+    }
+
+    #[test]
+    fn test_sqlite_people() -> Result<(), Box<dyn Error>> {
+        let top = env!("CARGO_MANIFEST_DIR");
+        let page = std::fs::read_to_string(format!("{}/test-data/test8.html", top))?;
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("rows.sqlite");
+
+        let mut writer = SqliteWriter::create(&path)?;
+        let rows = extract_rows(17, &page, &Aliases::default())?;
+        for row in &rows {
+            writer.write(row)?;
+        }
+        // The same professional under another name in a later series.
+        let mut renamed = rows[1].clone();
+        renamed.series = 18;
+        renamed.professional = "Karen Clifton".to_owned();
+        writer.write(&renamed)?;
+        // Different people with the same name and no article, in two series.
+        let mut unknown = rows[0].clone();
+        unknown.celebrity = "Emma".to_owned();
+        unknown.celebrity_id = None;
+        writer.write(&unknown)?;
+        unknown.series = 18;
+        writer.write(&unknown)?;
+        writer.finish()?;
+        let conn = Connection::open(&path)?;
+        let count = |sql: &str| conn.query_row(sql, [], |row| row.get::<_, i64>(0));
+        let professionals = count("SELECT COUNT(*) FROM professionals")?;
+        let emmas = count("SELECT COUNT(*) FROM celebrities WHERE name = 'Emma'")?;
+        let karen_couples = count(
+            "SELECT COUNT(*) FROM couples
+            JOIN professionals ON professionals.id = couples.professional_id
+            WHERE professionals.wiki_id = 'Karen_Hauer'",
+        )?;
+        assert_eq!(professionals, 4);
+        assert_eq!(emmas, 2);
+        assert_eq!(karen_couples, 2);
+        Ok(())
+    }
+}
@@ -0,0 +1,267 @@
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::str::FromStr;
+
+/// The family a dance belongs to, written as the `dance_type` column. The
+/// standard ballroom and Latin dances are danced from the first week; the
+/// freestyle dances, such as the Charleston, Showdance and Couple's Choice, are
+/// introduced later in a series.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub enum Category {
+    Ballroom,
+    Latin,
+    Freestyle,
+}
+impl fmt::Display for Category {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Category::Ballroom => "Ballroom",
+            Category::Latin => "Latin",
+            Category::Freestyle => "Freestyle",
+        })
+    }
+}
+
+/// A single dance style.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DanceStyle {
+    AmericanSmooth,
+    ArgentineTango,
+    ChaChaCha,
+    Charleston,
+    Contemporary,
+    CouplesChoice,
+    Foxtrot,
+    Jive,
+    LindyHop,
+    PasoDoble,
+    Quickstep,
+    RockNRoll,
+    Rumba,
+    Salsa,
+    Samba,
+    Showdance,
+    StreetCommercial,
+    Tango,
+    TheatreJazz,
+    VienneseWaltz,
+    Waltz,
+}
+impl DanceStyle {
+    const ALL: [DanceStyle; 21] = [
+        DanceStyle::AmericanSmooth,
+        DanceStyle::ArgentineTango,
+        DanceStyle::ChaChaCha,
+        DanceStyle::Charleston,
+        DanceStyle::Contemporary,
+        DanceStyle::CouplesChoice,
+        DanceStyle::Foxtrot,
+        DanceStyle::Jive,
+        DanceStyle::LindyHop,
+        DanceStyle::PasoDoble,
+        DanceStyle::Quickstep,
+        DanceStyle::RockNRoll,
+        DanceStyle::Rumba,
+        DanceStyle::Salsa,
+        DanceStyle::Samba,
+        DanceStyle::Showdance,
+        DanceStyle::StreetCommercial,
+        DanceStyle::Tango,
+        DanceStyle::TheatreJazz,
+        DanceStyle::VienneseWaltz,
+        DanceStyle::Waltz,
+    ];
+
+    /// The name used in the output.
+    pub fn name(self) -> &'static str {
+        match self {
+            DanceStyle::AmericanSmooth => "American Smooth",
+            DanceStyle::ArgentineTango => "Argentine Tango",
+            DanceStyle::ChaChaCha => "Cha-Cha-Cha",
+            DanceStyle::Charleston => "Charleston",
+            DanceStyle::Contemporary => "Contemporary",
+            DanceStyle::CouplesChoice => "Couple's Choice",
+            DanceStyle::Foxtrot => "Foxtrot",
+            DanceStyle::Jive => "Jive",
+            DanceStyle::LindyHop => "Lindy Hop",
+            DanceStyle::PasoDoble => "Paso Doble",
+            DanceStyle::Quickstep => "Quickstep",
+            DanceStyle::RockNRoll => "Rock 'n' Roll",
+            DanceStyle::Rumba => "Rumba",
+            DanceStyle::Salsa => "Salsa",
+            DanceStyle::Samba => "Samba",
+            DanceStyle::Showdance => "Showdance",
+            DanceStyle::StreetCommercial => "Street/Commercial",
+            DanceStyle::Tango => "Tango",
+            DanceStyle::TheatreJazz => "Theatre/Jazz",
+            DanceStyle::VienneseWaltz => "Viennese Waltz",
+            DanceStyle::Waltz => "Waltz",
+        }
+    }
+
+    pub fn category(self) -> Category {
+        match self {
+            DanceStyle::Foxtrot
+            | DanceStyle::Quickstep
+            | DanceStyle::Tango
+            | DanceStyle::VienneseWaltz
+            | DanceStyle::Waltz => Category::Ballroom,
+            DanceStyle::ChaChaCha
+            | DanceStyle::Jive
+            | DanceStyle::PasoDoble
+            | DanceStyle::Rumba
+            | DanceStyle::Samba => Category::Latin,
+            _ => Category::Freestyle,
+        }
+    }
+}
+impl fmt::Display for DanceStyle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+impl FromStr for DanceStyle {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // Names are compared ignoring case, spaces and punctuation, so
+        // "Cha cha cha", "Paso doble" and "Rock'n'Roll" are all recognized.
+        let key = |name: &str| {
+            name.chars()
+                .filter(|c| c.is_alphanumeric())
+                .flat_map(char::to_lowercase)
+                .collect::<String>()
+        };
+        let s_key = key(s);
+        if let Some(style) = DanceStyle::ALL
+            .iter()
+            .find(|style| key(style.name()) == s_key)
+        {
+            return Ok(*style);
+        }
+        // Earlier names for some styles.
+        match s_key.as_str() {
+            "street" => Ok(DanceStyle::StreetCommercial),
+            "jazz" => Ok(DanceStyle::TheatreJazz),
+            _ => Err(format!("unrecognized dance {:?}", s)),
+        }
+    }
+}
+
+/// A dance as named in a Week table: a single style, or a fusion of two
+/// styles such as "Charleston/Quickstep".
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Dance {
+    Single(DanceStyle),
+    Fusion(DanceStyle, DanceStyle),
+}
+impl Dance {
+    /// A fusion of two ballroom or two Latin styles keeps that category, and
+    /// any other fusion is freestyle.
+    pub fn category(self) -> Category {
+        match self {
+            Dance::Single(style) => style.category(),
+            Dance::Fusion(first, second) if first.category() == second.category() => {
+                first.category()
+            }
+            Dance::Fusion(_, _) => Category::Freestyle,
+        }
+    }
+}
+impl fmt::Display for Dance {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Dance::Single(style) => write!(f, "{}", style),
+            Dance::Fusion(first, second) => write!(f, "{}/{}", first, second),
+        }
+    }
+}
+impl FromStr for Dance {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // Some single styles contain a slash, e.g. "Theatre/Jazz", so try the
+        // whole name before splitting it into a fusion.
+        if let Ok(style) = s.parse() {
+            return Ok(Dance::Single(style));
+        }
+        let fusion = s.split_once('/').or_else(|| s.split_once(" and "));
+        match fusion {
+            Some((first, second)) => match (first.parse(), second.parse()) {
+                (Ok(first), Ok(second)) => Ok(Dance::Fusion(first, second)),
+                _ => Err(format!("unrecognized dance {:?}", s)),
+            },
+            None => Err(format!("unrecognized dance {:?}", s)),
+        }
+    }
+}
+impl Serialize for Dance {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+impl<'de> Deserialize<'de> for Dance {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Category, Dance, DanceStyle};
+
+    #[test]
+    fn test_dance_names() {
+        let dance = |name: &str| name.parse::<Dance>();
+        let single = |style| Ok(Dance::Single(style));
+        assert_eq!(dance("Cha Cha Cha"), single(DanceStyle::ChaChaCha));
+        assert_eq!(dance("Cha-Cha-Cha"), single(DanceStyle::ChaChaCha));
+        assert_eq!(dance("Paso doble"), single(DanceStyle::PasoDoble));
+        assert_eq!(dance("Rock'n'Roll"), single(DanceStyle::RockNRoll));
+        assert_eq!(dance("Street"), single(DanceStyle::StreetCommercial));
+        assert_eq!(dance("Jazz"), single(DanceStyle::TheatreJazz));
+        assert_eq!(
+            dance("Street/Commercial"),
+            single(DanceStyle::StreetCommercial)
+        );
+        assert_eq!(dance("Theatre/Jazz"), single(DanceStyle::TheatreJazz));
+        assert_eq!(
+            dance("Charleston/Quickstep"),
+            Ok(Dance::Fusion(DanceStyle::Charleston, DanceStyle::Quickstep))
+        );
+        assert_eq!(
+            dance("Rumba and Tango"),
+            Ok(Dance::Fusion(DanceStyle::Rumba, DanceStyle::Tango))
+        );
+        assert!(dance("Hokey Cokey").is_err());
+        assert!(dance("Charleston/Hokey Cokey").is_err());
+
+        assert_eq!(
+            Dance::Fusion(DanceStyle::ChaChaCha, DanceStyle::Tango).to_string(),
+            "Cha-Cha-Cha/Tango"
+        );
+        assert_eq!(
+            Dance::Single(DanceStyle::CouplesChoice).to_string(),
+            "Couple's Choice"
+        );
+
+        assert_eq!(
+            Dance::Single(DanceStyle::Waltz).category(),
+            Category::Ballroom
+        );
+        assert_eq!(
+            Dance::Fusion(DanceStyle::Jive, DanceStyle::Samba).category(),
+            Category::Latin
+        );
+        assert_eq!(
+            Dance::Fusion(DanceStyle::Tango, DanceStyle::Rumba).category(),
+            Category::Freestyle
+        );
+        assert_eq!(
+            Dance::Single(DanceStyle::CouplesChoice).category(),
+            Category::Freestyle
+        );
+    }
+}
@@ -0,0 +1,105 @@
+use lol_html::errors::RewritingError;
+use std::error::Error;
+use std::fmt;
+
+/// Where in a series page an error occurred. Week 0 is used for tables that
+/// are not in a Week section, such as the Couples table. Rows are numbered in
+/// their table from the header row, which is row 1. Row 0 is used for errors
+/// outside a table, such as in a heading.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Location {
+    pub series: u16,
+    pub week: u16,
+    pub row: u8,
+}
+impl fmt::Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "series {} week {} row {}",
+            self.series, self.week, self.row
+        )
+    }
+}
+
+/// An error extracting the scores from a page, with the text that could not
+/// be understood.
+#[derive(Debug)]
+pub enum ExtractError {
+    UnknownDance(Location, String),
+    BadScore(Location, String),
+    UnknownResult(Location, String),
+    UnexpectedTableShape(Location, String),
+    UnresolvedCouple(Location, String),
+    // A couple whose celebrity could be any of several with the same short
+    // name, with their full names.
+    AmbiguousCouple(Location, String, Vec<String>),
+    BadHeading(Location, String),
+    // Scores whose marks do not match the judging panel, with the judges.
+    // The row is kept without the judges' marks.
+    UnmatchedMarks(Location, String, Vec<String>),
+    Rewriting(RewritingError),
+}
+impl fmt::Display for ExtractError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExtractError::UnknownDance(location, text) => {
+                write!(f, "{}: unknown dance {:?}", location, text)
+            }
+            ExtractError::BadScore(location, text) => {
+                write!(f, "{}: bad score {:?}", location, text)
+            }
+            ExtractError::UnknownResult(location, text) => {
+                write!(f, "{}: unknown result {:?}", location, text)
+            }
+            ExtractError::UnexpectedTableShape(location, text) => {
+                write!(f, "{}: unexpected table shape {}", location, text)
+            }
+            ExtractError::UnresolvedCouple(location, text) => {
+                write!(f, "{}: unresolved couple {:?}", location, text)
+            }
+            ExtractError::AmbiguousCouple(location, text, candidates) => {
+                write!(
+                    f,
+                    "{}: ambiguous couple {:?}, could be {}",
+                    location,
+                    text,
+                    candidates.join(" or ")
+                )
+            }
+            ExtractError::BadHeading(location, text) => {
+                write!(f, "{}: bad heading {:?}", location, text)
+            }
+            ExtractError::UnmatchedMarks(location, text, judges) => {
+                write!(
+                    f,
+                    "{}: marks {:?} do not match the judges {}",
+                    location,
+                    text,
+                    judges.join(", ")
+                )
+            }
+            ExtractError::Rewriting(error) => error.fmt(f),
+        }
+    }
+}
+impl Error for ExtractError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ExtractError::Rewriting(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+impl From<RewritingError> for ExtractError {
+    fn from(error: RewritingError) -> Self {
+        // Errors returned by our content handlers are wrapped by the rewriter.
+        match error {
+            RewritingError::ContentHandlerError(error) => match error.downcast::<ExtractError>() {
+                Ok(error) => *error,
+                Err(error) => ExtractError::Rewriting(RewritingError::ContentHandlerError(error)),
+            },
+            error => ExtractError::Rewriting(error),
+        }
+    }
+}
@@ -0,0 +1,2263 @@
+use crate::aliases::Aliases;
+use crate::dance::{Category, Dance};
+use crate::error::{ExtractError, Location};
+use lol_html::html_content::{Element, EndTag, TextChunk, UserData};
+use lol_html::{element, text, HtmlRewriter, Settings};
+use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::convert::TryInto;
+use std::error::Error;
+use std::fmt;
+use std::rc::Rc;
+use std::str::FromStr;
+
+// User data attached to text in sub-elements of a table cell.
+#[derive(Debug, PartialEq)]
+enum SubElement {
+    // Links and formatting, e.g. the song and artist in the Music column.
+    Other,
+    // Footnote references, e.g. "<sup>1</sup>".
+    Footnote,
+}
+
+trait TableHandler {
+    fn section_begin(&mut self, _anchor: &str) -> Result<(), Box<dyn Error + Send + Sync>> {
+        Ok(())
+    }
+    fn show_begin(&mut self, _show: u8, _title: &str) -> Result<(), Box<dyn Error + Send + Sync>> {
+        Ok(())
+    }
+    fn p_end(&mut self, _text: &str) -> Result<(), Box<dyn Error + Send + Sync>> {
+        Ok(())
+    }
+    fn tr_begin(&mut self, _tr: &Element) -> Result<(), Box<dyn Error + Send + Sync>> {
+        Ok(())
+    }
+    fn tr_end(&mut self, _tr: &EndTag) -> Result<(), Box<dyn Error + Send + Sync>> {
+        Ok(())
+    }
+    fn th_begin(&mut self, _th: &Element) -> Result<(), Box<dyn Error + Send + Sync>> {
+        Ok(())
+    }
+    fn th_text(&mut self, _t: &TextChunk) -> Result<(), Box<dyn Error + Send + Sync>> {
+        Ok(())
+    }
+    fn th_end(&mut self, _th: &EndTag) -> Result<(), Box<dyn Error + Send + Sync>> {
+        Ok(())
+    }
+    fn td_begin(&mut self, _td: &Element) -> Result<(), Box<dyn Error + Send + Sync>> {
+        Ok(())
+    }
+    fn td_break(&mut self, _td: &Element) -> Result<(), Box<dyn Error + Send + Sync>> {
+        Ok(())
+    }
+    fn td_link(&mut self, _a: &Element) -> Result<(), Box<dyn Error + Send + Sync>> {
+        Ok(())
+    }
+    fn td_end(&mut self, _td: &EndTag) -> Result<(), Box<dyn Error + Send + Sync>> {
+        Ok(())
+    }
+    fn td_text(&mut self, _t: &TextChunk) -> Result<(), Box<dyn Error + Send + Sync>> {
+        Ok(())
+    }
+}
+
+struct UnrecognizedTable {}
+impl UnrecognizedTable {
+    fn new() -> UnrecognizedTable {
+        UnrecognizedTable {}
+    }
+}
+impl TableHandler for UnrecognizedTable {}
+
+// The names of the people in a series, from the Couples table.
+#[derive(Debug, Default)]
+struct People {
+    // The short names used in the Week tables to full names. Celebrities can
+    // share a short name, so each has every celebrity it could be.
+    celeb_moniker_to_name: HashMap<String, Vec<String>>,
+    pro_moniker_to_name: HashMap<String, String>,
+    // Full names to the IDs of the people.
+    ids: HashMap<String, String>,
+    // The full names of the professionals each celebrity danced with.
+    partners: HashMap<String, Vec<String>>,
+}
+
+#[derive(Debug, PartialEq)]
+enum CoupleExpect {
+    NewRow,
+    Celebrity,
+    KnownFor,
+    Professional,
+    Status,
+    EndRow,
+}
+#[derive(Debug)]
+struct CoupleTable {
+    state: CoupleExpect,
+    series: u16,
+    row: u8,
+    celebrity: String,
+    known_for: String,
+    professional: String,
+    status: String,
+    // The Wikipedia links in the Celebrity cell, and in each line of the
+    // Professional cell.
+    celebrity_id: Option<String>,
+    professional_ids: Vec<Option<String>>,
+    output: Rc<RefCell<Output>>,
+    people: Rc<RefCell<People>>,
+    aliases: Rc<Aliases>,
+}
+impl CoupleTable {
+    fn new(
+        output: Rc<RefCell<Output>>,
+        people: Rc<RefCell<People>>,
+        aliases: Rc<Aliases>,
+        series: u16,
+    ) -> CoupleTable {
+        CoupleTable {
+            state: CoupleExpect::NewRow,
+            series,
+            row: 0,
+            celebrity: String::new(),
+            known_for: String::new(),
+            professional: String::new(),
+            status: String::new(),
+            celebrity_id: None,
+            professional_ids: Vec::new(),
+            output,
+            people,
+            aliases,
+        }
+    }
+    fn location(&self) -> Location {
+        Location {
+            series: self.series,
+            week: 0,
+            row: self.row,
+        }
+    }
+    fn add_celeb_name(&self, moniker: String, full_name: &str) {
+        // Two contestants can have the same moniker! Keep them all, to be told
+        // apart by their professional partner.
+        let mut people = self.people.borrow_mut();
+        let names = people.celeb_moniker_to_name.entry(moniker).or_default();
+        if !names.iter().any(|name| name == full_name) {
+            names.push(full_name.to_owned());
+        }
+    }
+    fn add_celeb_names(&self, full_name: &str) {
+        // Create a mapping of contestant monikers (short name on the show
+        // and in the Week tables) to their full names. Rather than work out
+        // their monikers we just create the common transformations and plug
+        // them in. If doing this creates duplicates, where the same moniker
+        // could be two celebs (e.g. same first name), the moniker maps to both.
+        if let Some(moniker) = self.aliases.moniker(full_name) {
+            // the exceptions to the rules
+            self.add_celeb_name(moniker.to_owned(), full_name);
+        } else {
+            let mut names = full_name.split(' ');
+            // Split returns at least one item so this `unwrap` will not panic
+            let first_name = names.next().unwrap().to_owned();
+            if let Some(second_name) = names.next() {
+                // Some celebs are represented by first name and initial of their surname.
+                // e.g two Ricky's in series 7, two Emma's in series 17
+                if let Some(initial) = second_name.chars().next() {
+                    let name_initial = format!("{} {}.", first_name, initial);
+                    self.add_celeb_name(name_initial, full_name);
+                }
+                // A few are represented by their 2 first "names":
+                // Dr. Ranj Singh -> Dr. Ranj
+                // Judge Rinder -> Judge Rinder
+                // Rev. Richard Coles -> Rev. Richard
+                let two_names = format!("{} {}", first_name, second_name);
+                self.add_celeb_name(two_names, full_name);
+            }
+            // Most are represented by their first (or only) name in the Week tables.
+            self.add_celeb_name(first_name, full_name);
+        }
+    }
+    fn add_pro_names(&self, full_name: &str) {
+        let mut names = full_name.split(' ');
+        // Split returns at least one item so this `unwrap` will not panic
+        let first_name = names.next().unwrap().to_owned();
+        self.people
+            .borrow_mut()
+            .pro_moniker_to_name
+            .insert(first_name, full_name.to_owned());
+    }
+}
+impl TableHandler for CoupleTable {
+    fn tr_begin(&mut self, _tr: &Element) -> Result<(), Box<dyn Error + Send + Sync>> {
+        self.row += 1;
+        self.state = match self.state {
+            CoupleExpect::NewRow => CoupleExpect::Celebrity,
+            ref other => {
+                return Err(ExtractError::UnexpectedTableShape(
+                    self.location(),
+                    format!("new row while expecting {:?}", other),
+                )
+                .into());
+            }
+        };
+        Ok(())
+    }
+    fn tr_end(&mut self, _tr: &EndTag) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let celebrity_decoded = html_escape::decode_html_entities(&self.celebrity);
+        let celebrity = celebrity_decoded.trim();
+        // The header row has no td cells.
+        if !celebrity.is_empty() {
+            self.add_celeb_names(celebrity);
+            if let Some(id) = &self.celebrity_id {
+                self.people
+                    .borrow_mut()
+                    .ids
+                    .insert(celebrity.to_owned(), id.clone());
+            }
+
+            // Where a celebrity dances with more than one professional during a series, we will have
+            // their names separated by semi-colons. e.g.
+            // Robin Windsor;Brendan Cole (Week 9)
+            let professional_decoded = html_escape::decode_html_entities(&self.professional);
+            let mut professionals = Vec::new();
+            let mut professional_ids = Vec::new();
+            for (line, professional) in professional_decoded.split(';').enumerate() {
+                // Split returns at least one item so this `unwrap` will not panic
+                let name = professional.split('(').next().unwrap().trim();
+                if name.is_empty() {
+                    continue;
+                }
+                self.add_pro_names(name);
+                self.people
+                    .borrow_mut()
+                    .partners
+                    .entry(celebrity.to_owned())
+                    .or_default()
+                    .push(name.to_owned());
+                let id = self.professional_ids.get(line).cloned().flatten();
+                if let Some(id) = &id {
+                    self.people
+                        .borrow_mut()
+                        .ids
+                        .insert(name.to_owned(), id.clone());
+                }
+                let (name, _) = self.aliases.professional(name);
+                professionals.push(name.to_owned());
+                professional_ids.push(id.unwrap_or_default());
+            }
+
+            let known_for = html_escape::decode_html_entities(&self.known_for);
+            self.output.borrow_mut().couples.push(Couple {
+                series: self.series,
+                celebrity: celebrity.to_owned(),
+                celebrity_id: self.celebrity_id.clone(),
+                known_for: known_for.split_whitespace().collect::<Vec<_>>().join(" "),
+                professional: professionals.join("; "),
+                professional_id: professional_ids.join("; "),
+                professional_weeks: String::new(),
+                status: parse_status(&self.status),
+                last_week: None,
+            });
+        }
+
+        self.state = CoupleExpect::NewRow;
+        self.celebrity.clear();
+        self.known_for.clear();
+        self.professional.clear();
+        self.status.clear();
+        self.celebrity_id = None;
+        self.professional_ids.clear();
+        Ok(())
+    }
+
+    fn td_break(&mut self, _td: &Element) -> Result<(), Box<dyn Error + Send + Sync>> {
+        match self.state {
+            CoupleExpect::Celebrity => {
+                self.celebrity.push(';');
+            }
+            CoupleExpect::KnownFor => {
+                self.known_for.push(' ');
+            }
+            CoupleExpect::Professional => {
+                self.professional.push(';');
+            }
+            CoupleExpect::Status => {
+                // "Eliminated 3rd<br />on 4 October 2020"
+                self.status.push(';');
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+    fn td_link(&mut self, a: &Element) -> Result<(), Box<dyn Error + Send + Sync>> {
+        // Use the first link to a Wikipedia article for each person.
+        let mut id = match a.get_attribute("href").as_deref().and_then(wiki_id) {
+            Some(id) => id,
+            None => return Ok(()),
+        };
+        // A link to a redirect, such as an earlier name of the person, is not
+        // the ID of their article unless the aliases say where it goes.
+        let redirect = a
+            .get_attribute("class")
+            .is_some_and(|class| class.split_whitespace().any(|c| c == "mw-redirect"));
+        if redirect {
+            id = match self.aliases.redirect(&id) {
+                Some(target) => target.to_owned(),
+                None => return Ok(()),
+            };
+        }
+        match self.state {
+            CoupleExpect::Celebrity => {
+                self.celebrity_id.get_or_insert(id);
+            }
+            CoupleExpect::Professional => {
+                let line = self.professional.matches(';').count();
+                if self.professional_ids.len() <= line {
+                    self.professional_ids.resize(line + 1, None);
+                }
+                self.professional_ids[line].get_or_insert(id);
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+    fn td_end(&mut self, _td: &EndTag) -> Result<(), Box<dyn Error + Send + Sync>> {
+        self.state = match self.state {
+            CoupleExpect::Celebrity => CoupleExpect::KnownFor,
+            CoupleExpect::KnownFor => CoupleExpect::Professional,
+            CoupleExpect::Professional => CoupleExpect::Status,
+            CoupleExpect::Status | CoupleExpect::EndRow => CoupleExpect::EndRow,
+            ref other => {
+                return Err(ExtractError::UnexpectedTableShape(
+                    self.location(),
+                    format!("end of cell while expecting {:?}", other),
+                )
+                .into());
+            }
+        };
+        Ok(())
+    }
+    fn td_text(&mut self, t: &TextChunk) -> Result<(), Box<dyn Error + Send + Sync>> {
+        if t.user_data().downcast_ref::<SubElement>() == Some(&SubElement::Footnote) {
+            // ignore footnotes
+            return Ok(());
+        }
+        match self.state {
+            CoupleExpect::Celebrity => {
+                self.celebrity.push_str(t.as_str());
+            }
+            CoupleExpect::KnownFor => {
+                self.known_for.push_str(t.as_str());
+            }
+            CoupleExpect::Professional => {
+                self.professional.push_str(t.as_str());
+            }
+            CoupleExpect::Status => {
+                self.status.push_str(t.as_str());
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+}
+
+// The columns of a Week table, identified by the text of their header cells.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum WeekColumn {
+    Couple,
+    Score,
+    Dance,
+    Music,
+    Result,
+    // Columns we do not extract, e.g. "Order".
+    Other,
+}
+impl WeekColumn {
+    fn from_header(header: &str) -> WeekColumn {
+        // Headers can have a footnote, e.g. "Dance[13]".
+        let header = header.split('[').next().unwrap_or_default().trim();
+        match header.to_lowercase().as_str() {
+            "couple" | "couples" => WeekColumn::Couple,
+            "score" | "scores" => WeekColumn::Score,
+            "dance" | "dances" => WeekColumn::Dance,
+            "music" => WeekColumn::Music,
+            "result" => WeekColumn::Result,
+            _ => WeekColumn::Other,
+        }
+    }
+    fn is_required(self) -> bool {
+        matches!(
+            self,
+            WeekColumn::Couple | WeekColumn::Score | WeekColumn::Dance
+        )
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum WeekExpect {
+    NewRow,
+    // In a header row, collecting the text of each header cell.
+    Header,
+    // In the cell for the column with this index.
+    Column(usize),
+    EndRow,
+}
+// The people in a row of a Week table.
+struct Partners {
+    celebrity: String,
+    celebrity_id: Option<String>,
+    professional: String,
+    professional_id: Option<String>,
+    credited_professional: String,
+    note: String,
+}
+
+#[derive(Debug)]
+struct WeekTable {
+    state: WeekExpect,
+    series: u16,
+    week: u16,
+    week_theme: String,
+    show: Option<u8>,
+    show_title: String,
+    running_order: u8,
+    // The heading anchor of the current section, e.g. "Week_5", and the
+    // number of the current row in its table, where the header row is 1.
+    section: String,
+    table_row: u8,
+    // The columns of the current table, from its header row, and the number
+    // of rows still spanned by the last cell in each column. The header cells
+    // of the current row are kept with their spans until the end of the row,
+    // as the row is a data row if it also has data cells.
+    headers: Vec<String>,
+    header_spans: Vec<(u8, u8)>,
+    columns: Vec<WeekColumn>,
+    column_uses: Vec<u8>,
+    // The rows of a table we do not recognise are ignored, until the next
+    // header row or section.
+    ignore_rows: bool,
+    // A cell in the current row spans the Score column from another column,
+    // e.g. "All couples" over Couple and Score for a group dance.
+    score_spanned: bool,
+    couple: String,
+    score: String,
+    dance: String,
+    combined_dance: bool,
+    music: String,
+    result: String,
+    note: String,
+    judges: Rc<RefCell<Judges>>,
+    output: Rc<RefCell<Output>>,
+    people: Rc<RefCell<People>>,
+    aliases: Rc<Aliases>,
+}
+impl WeekTable {
+    fn new_for_week(
+        output: Rc<RefCell<Output>>,
+        people: Rc<RefCell<People>>,
+        aliases: Rc<Aliases>,
+        judges: Rc<RefCell<Judges>>,
+        series: u16,
+        week: u16,
+    ) -> Self {
+        WeekTable {
+            output,
+            people,
+            aliases,
+            state: WeekExpect::NewRow,
+            series,
+            week,
+            week_theme: String::new(),
+            show: None,
+            show_title: String::new(),
+            running_order: 0,
+            section: String::new(),
+            table_row: 0,
+            headers: Vec::new(),
+            header_spans: Vec::new(),
+            columns: Vec::new(),
+            column_uses: Vec::new(),
+            ignore_rows: false,
+            score_spanned: false,
+            couple: String::new(),
+            score: String::new(),
+            dance: String::new(),
+            combined_dance: false,
+            music: String::new(),
+            result: String::new(),
+            note: String::new(),
+            judges,
+        }
+    }
+    fn location(&self) -> Location {
+        Location {
+            series: self.series,
+            week: self.week,
+            row: self.table_row,
+        }
+    }
+    fn split_couple(&self, couple: &str) -> Result<Partners, ExtractError> {
+        // Split a string "Celeb & Professional" into the full names of the celebrity and
+        // professional, with their IDs if the Couples table links to them.
+        let unresolved = || ExtractError::UnresolvedCouple(self.location(), couple.to_owned());
+        let mut names = couple.split(" & ");
+        // Split returns at least one item so this `unwrap` will not panic
+        let celeb_moniker = names.next().unwrap();
+        // Some couples have an asterisk at the end to refer to a footnote.
+        let pro_moniker = names.next().ok_or_else(unresolved)?.trim_end_matches('*');
+        if names.next().is_some() {
+            return Err(unresolved());
+        }
+        let people = self.people.borrow();
+        let mut note = self.note.clone();
+        let pro_moniker_to_name = &people.pro_moniker_to_name;
+        let mut professional_id = None;
+        // The professional's full name on the page, to tell apart celebrities
+        // with the same moniker.
+        let mut pro_name = None;
+        let professional = match pro_moniker_to_name.get(pro_moniker) {
+            Some(name) if !name.is_empty() => {
+                professional_id = people.ids.get(name).cloned();
+                pro_name = Some(name);
+                // Wikipedia is inconsistent with the spelling of some names.
+                // The name as credited is kept for `ProRegistry`, which gives
+                // professionals who danced under more than one name a
+                // consistent name.
+                let (name, name_note) = self.aliases.professional(name);
+                if let Some(name_note) = name_note {
+                    if !note.is_empty() {
+                        note.push_str("; ");
+                    }
+                    note.push_str(name_note);
+                }
+                name.to_owned()
+            }
+            _ if pro_moniker_to_name.is_empty() => pro_moniker.to_owned(),
+            _ => {
+                self.output.borrow_mut().warn(unresolved())?;
+                pro_moniker.to_owned()
+            }
+        };
+        // Convert the short celeb name to a full name. If the page has no Couples
+        // table there are no full names, so keep the moniker. Keep it too if
+        // the moniker cannot be resolved, with a warning, so that the scores
+        // are not lost.
+        let celeb_moniker_to_name = &people.celeb_moniker_to_name;
+        let celebrity = match celeb_moniker_to_name.get(celeb_moniker).map(Vec::as_slice) {
+            Some([name]) => name.clone(),
+            Some(candidates) => {
+                // Several celebrities have this moniker, e.g. two Emmas in
+                // series 17. Use the one who danced with this professional.
+                let partnered = |name: &&String| match (people.partners.get(*name), pro_name) {
+                    (Some(partners), Some(pro_name)) => partners.contains(pro_name),
+                    _ => false,
+                };
+                match candidates.iter().filter(partnered).collect::<Vec<_>>()[..] {
+                    [name] => name.clone(),
+                    _ => {
+                        self.output
+                            .borrow_mut()
+                            .warn(ExtractError::AmbiguousCouple(
+                                self.location(),
+                                couple.to_owned(),
+                                candidates.to_vec(),
+                            ))?;
+                        celeb_moniker.to_owned()
+                    }
+                }
+            }
+            None if celeb_moniker_to_name.is_empty() => celeb_moniker.to_owned(),
+            None => {
+                self.output.borrow_mut().warn(unresolved())?;
+                celeb_moniker.to_owned()
+            }
+        };
+        let celebrity_id = people.ids.get(&celebrity).cloned();
+        let credited_professional = pro_name.unwrap_or(&professional).clone();
+        Ok(Partners {
+            celebrity,
+            celebrity_id,
+            professional,
+            professional_id,
+            credited_professional,
+            note,
+        })
+    }
+    fn extract_row(&self, couple: &str) -> Result<Option<Row>, ExtractError> {
+        let Partners {
+            celebrity,
+            celebrity_id,
+            professional,
+            professional_id,
+            credited_professional,
+            note,
+        } = self.split_couple(couple)?;
+        let scores_decoded = html_escape::decode_html_entities(&self.score);
+        let scores = scores_decoded.trim();
+        match scores.split_once(' ') {
+            None => {
+                // No space in scores. Perhaps "N/A" for unscored showdance.
+                const NONSCORED: [&str; 4] = ["Showdance", "N/A", "", "*"];
+                if NONSCORED.contains(&scores) {
+                    Ok(None)
+                } else {
+                    Err(ExtractError::BadScore(self.location(), scores.to_owned()))
+                }
+            }
+            Some((first, remainder)) => {
+                if let Ok(total_score) = u8::from_str(first) {
+                    let bad_score = || ExtractError::BadScore(self.location(), scores.to_owned());
+                    // The remainder is the individual judges' scores, e.g. "(6,8,8,7)"
+                    let marks = remainder
+                        .trim()
+                        .trim_start_matches('(')
+                        .trim_end_matches(')')
+                        .split(',')
+                        .map(|mark| u8::from_str(mark.trim()))
+                        .collect::<Result<Vec<u8>, _>>()
+                        .map_err(|_| bad_score())?;
+                    let score_count: u8 = marks.len().try_into().map_err(|_| bad_score())?;
+                    let avg_score = f32::from(total_score) / f32::from(score_count);
+                    if !(1.0..=10.0).contains(&avg_score) {
+                        return Err(bad_score());
+                    }
+                    let (song, artist) = split_music(&self.music);
+                    let dance_decoded = html_escape::decode_html_entities(&self.dance);
+                    let dance = self.aliases.dance(dance_decoded.trim());
+                    let dance = dance.parse::<Dance>().map_err(|_| {
+                        ExtractError::UnknownDance(self.location(), dance_decoded.trim().to_owned())
+                    })?;
+                    // Keep the scores of a row whose result we do not know,
+                    // e.g. "Immune", without the result.
+                    let result = match parse_result(&self.result) {
+                        Ok(result) => result,
+                        Err(result) => {
+                            self.output
+                                .borrow_mut()
+                                .warn(ExtractError::UnknownResult(self.location(), result))?;
+                            None
+                        }
+                    };
+                    let mut row = Row {
+                        series: self.series,
+                        week: self.week,
+                        celebrity,
+                        professional,
+                        dance,
+                        total_score,
+                        score_count,
+                        avg_score,
+                        craig: None,
+                        arlene: None,
+                        len: None,
+                        bruno: None,
+                        alesha: None,
+                        darcey: None,
+                        shirley: None,
+                        motsi: None,
+                        anton: None,
+                        guest: None,
+                        guest_judge: String::new(),
+                        result,
+                        song,
+                        artist,
+                        show: self.show,
+                        show_title: self.show_title.clone(),
+                        running_order: self.running_order,
+                        week_theme: self.week_theme.clone(),
+                        note,
+                        partial: false,
+                        dance_type: Some(dance.category()),
+                        celebrity_id,
+                        professional_id,
+                        credited_professional,
+                        section: self.section.clone(),
+                        table_row: self.table_row,
+                        provenance: None,
+                    };
+                    let judges = self.judges.borrow();
+                    let panel = judges.panel();
+                    // Without a judging panel on the page we cannot tell whose
+                    // mark is whose, so leave the individual scores empty.
+                    // Nor if the marks do not match the panel, e.g. a mark is
+                    // missing or there is a second guest judge, but keep the
+                    // total score.
+                    if !panel.is_empty() {
+                        let mut judged = row.clone();
+                        if panel.len() == marks.len()
+                            && panel
+                                .iter()
+                                .zip(marks)
+                                .all(|(judge, mark)| judged.add_judge_score(judge, mark))
+                        {
+                            row = judged;
+                        } else {
+                            self.output.borrow_mut().warn(ExtractError::UnmatchedMarks(
+                                self.location(),
+                                scores.to_owned(),
+                                panel.to_vec(),
+                            ))?;
+                        }
+                    }
+                    Ok(Some(row))
+                } else if scores == "Not scored" {
+                    Ok(None)
+                } else {
+                    Err(ExtractError::BadScore(self.location(), scores.to_owned()))
+                }
+            }
+        }
+    }
+    fn add_theme(&mut self, theme: &str) {
+        // A week can have more than one theme, e.g. "Blackpool; Halloween"
+        if !theme.is_empty() && !self.week_theme.contains(theme) {
+            if !self.week_theme.is_empty() {
+                self.week_theme.push_str("; ");
+            }
+            self.week_theme.push_str(theme);
+        }
+    }
+    fn expect_after(&self, column: Option<usize>) -> WeekExpect {
+        // The next column to expect in a row, skipping any columns that are
+        // still spanned by a cell from a previous row.
+        let next = column.map_or(0, |column| column + 1);
+        match (next..self.columns.len()).find(|&column| self.column_uses[column] == 0) {
+            Some(column) => WeekExpect::Column(column),
+            None => WeekExpect::EndRow,
+        }
+    }
+    fn current_column(&self) -> Option<WeekColumn> {
+        match self.state {
+            WeekExpect::Column(column) => self.columns.get(column).copied(),
+            _ => None,
+        }
+    }
+    fn set_columns(&mut self) -> Result<(), ExtractError> {
+        // Map the cells in each row to the columns named in the header row.
+        // Each column we extract must appear once, apart from the optional
+        // Music and Result columns which may be missing.
+        let columns: Vec<WeekColumn> = self
+            .headers
+            .iter()
+            .map(|header| WeekColumn::from_header(header))
+            .collect();
+        let count = |column| columns.iter().filter(|&&c| c == column).count();
+        let layout_ok = [
+            WeekColumn::Couple,
+            WeekColumn::Score,
+            WeekColumn::Dance,
+            WeekColumn::Music,
+            WeekColumn::Result,
+        ]
+        .iter()
+        .all(|&column| match count(column) {
+            0 => !column.is_required(),
+            1 => true,
+            _ => false,
+        });
+        if !layout_ok {
+            return Err(ExtractError::UnexpectedTableShape(
+                self.location(),
+                format!("week table columns {:?}", self.headers),
+            ));
+        }
+        self.column_uses = vec![0; columns.len()];
+        self.columns = columns;
+        Ok(())
+    }
+    fn cell_span(&self, cell: &Element) -> Result<(u8, u8), ExtractError> {
+        let span = |name: &str| match cell.get_attribute(name) {
+            Some(span) => span.parse().map_err(|_| {
+                ExtractError::UnexpectedTableShape(self.location(), format!("{} {:?}", name, span))
+            }),
+            None => Ok(1),
+        };
+        Ok((span("rowspan")?, span("colspan")?))
+    }
+    fn cell_begin(&mut self, rows: u8, columns: u8) -> Result<(), ExtractError> {
+        if self.state == WeekExpect::NewRow {
+            if self.columns.is_empty() {
+                let error = ExtractError::UnexpectedTableShape(
+                    self.location(),
+                    "week table has no header row".to_owned(),
+                );
+                return self.ignore_table(error);
+            }
+            self.state = self.expect_after(None);
+        }
+        let column = match self.state {
+            WeekExpect::Column(column) => column,
+            WeekExpect::EndRow => {
+                // skip cells beyond the columns in the header
+                return Ok(());
+            }
+            ref other => {
+                let error = ExtractError::UnexpectedTableShape(
+                    self.location(),
+                    format!("new cell while expecting {:?}", other),
+                );
+                return self.ignore_table(error);
+            }
+        };
+        // When couples dance multiple dances in a show, the Couple column will
+        // have a rowspan > 1. Keep the rowspan as the repeat count. A cell
+        // with a colspan also fills the columns after it, which are skipped.
+        let spanned = column..(column + usize::from(columns.max(1))).min(self.columns.len());
+        for spanned_column in spanned.clone() {
+            self.column_uses[spanned_column] = rows;
+        }
+        for spanned_column in spanned.skip(1) {
+            match self.columns[spanned_column] {
+                WeekColumn::Couple => self.couple.clear(),
+                WeekColumn::Score => self.score_spanned = true,
+                WeekColumn::Dance => self.dance.clear(),
+                WeekColumn::Music => self.music.clear(),
+                WeekColumn::Result => self.result.clear(),
+                WeekColumn::Other => {}
+            }
+        }
+        match self.columns[column] {
+            WeekColumn::Couple => {
+                self.couple.clear();
+            }
+            WeekColumn::Score => {
+                self.score.clear();
+                if rows > 1 {
+                    // In Series 10, Week 10 couples danced two styles in one dance. For
+                    // this week, the scores have rowspan > 1.
+                    let len = self.note.len();
+                    self.note.replace_range(..len, "combined dance");
+                    self.dance.clear();
+                    self.combined_dance = true;
+                } else {
+                    self.note.clear();
+                }
+            }
+            WeekColumn::Dance => {
+                if !self.dance.is_empty() {
+                    if self.combined_dance {
+                        self.dance.retain(|c| c != '\n');
+                        self.dance.push('/');
+                        self.combined_dance = false;
+                    } else {
+                        self.dance.clear();
+                    }
+                }
+            }
+            WeekColumn::Music => {
+                self.music.clear();
+            }
+            WeekColumn::Result => {
+                // When a couple dances more than once in a show, the Result
+                // column has the same rowspan as the Couple column.
+                self.result.clear();
+            }
+            WeekColumn::Other => {}
+        }
+        Ok(())
+    }
+    fn cell_end(&mut self) -> Result<(), ExtractError> {
+        match self.state {
+            WeekExpect::Column(column) => {
+                self.state = self.expect_after(Some(column));
+            }
+            WeekExpect::EndRow => {
+                // skip remaining columns
+            }
+            _ if self.ignore_rows => {}
+            ref other => {
+                let error = ExtractError::UnexpectedTableShape(
+                    self.location(),
+                    format!("end of cell while expecting {:?}", other),
+                );
+                self.ignore_table(error)?;
+            }
+        }
+        Ok(())
+    }
+    fn cell_text(&mut self, text: &str) {
+        match self.current_column() {
+            Some(WeekColumn::Couple) => {
+                self.couple.push_str(text);
+            }
+            Some(WeekColumn::Score) => {
+                self.score.push_str(text);
+            }
+            Some(WeekColumn::Dance) => {
+                self.dance.push_str(text);
+            }
+            Some(WeekColumn::Music) => {
+                self.music.push_str(text);
+            }
+            Some(WeekColumn::Result) => {
+                self.result.push_str(text);
+            }
+            _ => {}
+        }
+    }
+    fn header_cells_to_data(&mut self) -> Result<(), ExtractError> {
+        // A row with data cells is not a header row, so read the header cells
+        // so far as its first data cells, e.g. `<th scope="row">`.
+        let headers = std::mem::take(&mut self.headers);
+        let spans = std::mem::take(&mut self.header_spans);
+        self.state = WeekExpect::NewRow;
+        for (text, (rows, columns)) in headers.iter().zip(spans) {
+            if self.ignore_rows {
+                break;
+            }
+            self.cell_begin(rows, columns)?;
+            self.cell_text(text);
+            self.cell_end()?;
+        }
+        Ok(())
+    }
+    fn ignore_table(&mut self, error: ExtractError) -> Result<(), ExtractError> {
+        // Record an error in the layout of a table, and ignore the rest of
+        // it. This only stops the extraction in strict mode.
+        self.ignore_rows = true;
+        self.columns.clear();
+        self.column_uses.clear();
+        self.state = WeekExpect::EndRow;
+        self.output.borrow_mut().warn(error)
+    }
+    fn end_row(&mut self) {
+        for uses in &mut self.column_uses {
+            *uses = uses.saturating_sub(1);
+        }
+        self.score_spanned = false;
+        self.state = WeekExpect::NewRow;
+    }
+}
+impl TableHandler for WeekTable {
+    fn p_end(&mut self, text: &str) -> Result<(), Box<dyn Error + Send + Sync>> {
+        // The week introduction may name a theme that is not in the section
+        // title, e.g. "This week's theme was Musicals."
+        let lower = text.to_ascii_lowercase();
+        for (index, _) in lower.match_indices("theme") {
+            let after = &text[index + "theme".len()..];
+            let theme = [":", " was ", " is ", " of "]
+                .iter()
+                .find_map(|prefix| after.strip_prefix(prefix))
+                .and_then(|theme| theme.split(&['.', ',', ';', '['][..]).next())
+                .map(|theme| theme.trim().trim_matches('"').trim());
+            if let Some(theme) = theme {
+                self.add_theme(theme);
+            }
+        }
+        Ok(())
+    }
+    fn section_begin(&mut self, anchor: &str) -> Result<(), Box<dyn Error + Send + Sync>> {
+        self.section = anchor.to_owned();
+        self.ignore_rows = false;
+        Ok(())
+    }
+    fn show_begin(&mut self, show: u8, title: &str) -> Result<(), Box<dyn Error + Send + Sync>> {
+        self.show = Some(show);
+        self.show_title = title.to_owned();
+        self.running_order = 0;
+        Ok(())
+    }
+    fn tr_begin(&mut self, _tr: &Element) -> Result<(), Box<dyn Error + Send + Sync>> {
+        // Count every dance in the running order, including group dances that
+        // we do not output.
+        self.running_order += 1;
+        self.table_row += 1;
+        if self.state != WeekExpect::NewRow && !self.ignore_rows {
+            let error = ExtractError::UnexpectedTableShape(
+                self.location(),
+                format!("new row while expecting {:?}", self.state),
+            );
+            self.ignore_table(error)?;
+        }
+        self.state = WeekExpect::NewRow;
+        Ok(())
+    }
+    fn tr_end(&mut self, _tr: &EndTag) -> Result<(), Box<dyn Error + Send + Sync>> {
+        match self.state {
+            WeekExpect::NewRow => {
+                // A row with no cells.
+                return Ok(());
+            }
+            WeekExpect::Header => {
+                // The header row starts a new table, so restart the running
+                // order and the row numbers.
+                self.running_order = 0;
+                self.table_row = 1;
+                self.state = WeekExpect::NewRow;
+                match self.set_columns() {
+                    Ok(()) => self.ignore_rows = false,
+                    // Only report the first header row of a table we ignore.
+                    Err(_) if self.ignore_rows => {}
+                    Err(error) => self.ignore_table(error)?,
+                }
+                return Ok(());
+            }
+            _ if self.ignore_rows => {
+                self.state = WeekExpect::NewRow;
+                return Ok(());
+            }
+            WeekExpect::Column(column) => {
+                // Older tables have no Music or Result columns, and the row
+                // can end before any optional column.
+                if let Some(missing) = (column..self.columns.len()).find(|&column| {
+                    self.column_uses[column] == 0 && self.columns[column].is_required()
+                }) {
+                    let error = ExtractError::UnexpectedTableShape(
+                        self.location(),
+                        format!("end of row while expecting {:?}", self.columns[missing]),
+                    );
+                    self.end_row();
+                    return Ok(self.output.borrow_mut().warn(error)?);
+                }
+            }
+            WeekExpect::EndRow => {}
+        }
+        if self.score_spanned {
+            // A group dance, which is not scored.
+            self.end_row();
+            return Ok(());
+        }
+        if self.couple.is_empty() || self.score.is_empty() || self.dance.is_empty() {
+            let error = ExtractError::UnexpectedTableShape(
+                self.location(),
+                format!(
+                    "empty cell in row couple={:?} score={:?} dance={:?}",
+                    self.couple, self.score, self.dance
+                ),
+            );
+            self.end_row();
+            return Ok(self.output.borrow_mut().warn(error)?);
+        }
+        if !self.combined_dance {
+            let couple_decoded = html_escape::decode_html_entities(&self.couple);
+            let couple = couple_decoded.trim();
+            if couple.contains(';') {
+                // Group dance with multiple couples (e.g. Series 7 week 11).
+                // These are ranked rather than scored, so we ignore them.
+            } else {
+                let row = self.extract_row(couple);
+                self.output.borrow_mut().add(row)?;
+            }
+        }
+        self.end_row();
+        Ok(())
+    }
+    fn th_begin(&mut self, th: &Element) -> Result<(), Box<dyn Error + Send + Sync>> {
+        match self.state {
+            WeekExpect::NewRow => {
+                self.state = WeekExpect::Header;
+                self.headers.clear();
+                self.header_spans.clear();
+                self.headers.push(String::new());
+                self.header_spans.push(self.cell_span(th).unwrap_or((1, 1)));
+            }
+            WeekExpect::Header => {
+                self.headers.push(String::new());
+                self.header_spans.push(self.cell_span(th).unwrap_or((1, 1)));
+            }
+            // A header cell in a data row, e.g. `<th scope="row">`, is read
+            // as a data cell.
+            _ => self.td_begin(th)?,
+        }
+        Ok(())
+    }
+    fn th_text(&mut self, t: &TextChunk) -> Result<(), Box<dyn Error + Send + Sync>> {
+        if self.state != WeekExpect::Header {
+            return self.td_text(t);
+        }
+        if let Some(header) = self.headers.last_mut() {
+            header.push_str(&html_escape::decode_html_entities(t.as_str()));
+        }
+        Ok(())
+    }
+    fn th_end(&mut self, th: &EndTag) -> Result<(), Box<dyn Error + Send + Sync>> {
+        if self.state != WeekExpect::Header {
+            return self.td_end(th);
+        }
+        Ok(())
+    }
+    fn td_begin(&mut self, td: &Element) -> Result<(), Box<dyn Error + Send + Sync>> {
+        if self.state == WeekExpect::Header {
+            self.header_cells_to_data()?;
+        }
+        if self.ignore_rows {
+            return Ok(());
+        }
+        match self.cell_span(td) {
+            Ok((rows, columns)) => Ok(self.cell_begin(rows, columns)?),
+            Err(error) => Ok(self.ignore_table(error)?),
+        }
+    }
+    fn td_break(&mut self, _td: &Element) -> Result<(), Box<dyn Error + Send + Sync>> {
+        match self.current_column() {
+            Some(WeekColumn::Couple) => {
+                self.couple.push(';');
+            }
+            Some(WeekColumn::Score) => {
+                self.score.push(';');
+            }
+            Some(WeekColumn::Dance) => {
+                self.dance.push(';');
+            }
+            Some(WeekColumn::Music) => {
+                self.music.push(';');
+            }
+            Some(WeekColumn::Result) => {
+                self.result.push(';');
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+    fn td_end(&mut self, _td: &EndTag) -> Result<(), Box<dyn Error + Send + Sync>> {
+        Ok(self.cell_end()?)
+    }
+    fn td_text(&mut self, t: &TextChunk) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let column = self.current_column();
+        match t.user_data().downcast_ref::<SubElement>() {
+            Some(SubElement::Footnote) => {
+                // ignore footnotes
+                return Ok(());
+            }
+            Some(SubElement::Other) if column != Some(WeekColumn::Music) => {
+                // ignore text in sub-elements of td, except for the song and
+                // artist links in the Music column
+                return Ok(());
+            }
+            _ => {}
+        }
+        self.cell_text(t.as_str());
+        Ok(())
+    }
+}
+
+// Write empty text as a missing value: an empty CSV field, or null in JSON.
+mod empty_as_none {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub(super) fn serialize<S: Serializer>(text: &str, serializer: S) -> Result<S::Ok, S::Error> {
+        if text.is_empty() {
+            serializer.serialize_none()
+        } else {
+            serializer.serialize_some(text)
+        }
+    }
+    pub(super) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<String, D::Error> {
+        Ok(Option::<String>::deserialize(deserializer)?.unwrap_or_default())
+    }
+}
+
+/// A dance by one couple in one show, with its scores.
+///
+/// This is one row of the CSV output. Text that is missing from the page is
+/// empty, and written as an empty field or `null`. Columns added to the output
+/// over time have defaults, so older output files can be read.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Row {
+    pub series: u16,
+    pub week: u16,
+    pub celebrity: String,
+    pub professional: String,
+    pub dance: Dance,
+    pub total_score: u8,
+    pub score_count: u8,
+    pub avg_score: f32,
+    pub craig: Option<u8>,
+    pub arlene: Option<u8>,
+    pub len: Option<u8>,
+    pub bruno: Option<u8>,
+    pub alesha: Option<u8>,
+    pub darcey: Option<u8>,
+    pub shirley: Option<u8>,
+    pub motsi: Option<u8>,
+    pub anton: Option<u8>,
+    pub guest: Option<u8>,
+    #[serde(default, with = "empty_as_none")]
+    pub guest_judge: String,
+    pub result: Option<Outcome>,
+    #[serde(default, with = "empty_as_none")]
+    pub song: String,
+    #[serde(default, with = "empty_as_none")]
+    pub artist: String,
+    pub show: Option<u8>,
+    #[serde(default, with = "empty_as_none")]
+    pub show_title: String,
+    #[serde(default)]
+    pub running_order: u8,
+    #[serde(default, with = "empty_as_none")]
+    pub week_theme: String,
+    #[serde(with = "empty_as_none")]
+    pub note: String,
+    // The series has not finished: no couple has been named the winner yet.
+    #[serde(default)]
+    pub partial: bool,
+    // Ballroom, Latin or Freestyle, following from the dance. Missing from
+    // older output files.
+    pub dance_type: Option<Category>,
+    // The Wikipedia articles of the celebrity and professional, e.g.
+    // "Karen_Hauer", which identify them across series whatever name they
+    // used. Missing if the Couples table does not link to them.
+    pub celebrity_id: Option<String>,
+    pub professional_id: Option<String>,
+    // The professional's name as credited on the page, before any alias, for
+    // the registry of professionals.
+    #[serde(skip)]
+    pub(crate) credited_professional: String,
+    // Where on the page the row was found, written only as provenance.
+    #[serde(skip)]
+    pub(crate) section: String,
+    #[serde(skip)]
+    pub(crate) table_row: u8,
+    // Left out of the output unless `add_provenance` is called. The csv
+    // crate cannot write flattened fields, so the writers add it after the
+    // other columns themselves.
+    #[serde(skip)]
+    pub provenance: Option<Provenance>,
+}
+impl Row {
+    /// Add where the row came from to the output: the page URL and revision,
+    /// when it was fetched, and the section and table row on the page.
+    pub fn add_provenance(&mut self, url: &str, revision: Option<u64>, fetched: u64) {
+        self.provenance = Some(Provenance {
+            url: url.to_owned(),
+            revision,
+            fetched,
+            section: self.section.clone(),
+            row: self.table_row,
+        });
+    }
+    fn add_judge_score(&mut self, judge: &str, mark: u8) -> bool {
+        // Resident judges have their own column, identified by first name. Anyone
+        // else on the panel is a guest judge, named in the `guest_judge` column.
+        // Returns false if there is no column for the judge's score.
+        let column = match judge.split(' ').next() {
+            Some("Craig") => &mut self.craig,
+            Some("Arlene") => &mut self.arlene,
+            Some("Len") => &mut self.len,
+            Some("Bruno") => &mut self.bruno,
+            Some("Alesha") => &mut self.alesha,
+            Some("Darcey") => &mut self.darcey,
+            Some("Shirley") => &mut self.shirley,
+            Some("Motsi") => &mut self.motsi,
+            Some("Anton") => &mut self.anton,
+            _ => {
+                if self.guest.is_some() {
+                    return false;
+                }
+                self.guest_judge = judge.to_owned();
+                &mut self.guest
+            }
+        };
+        *column = Some(mark);
+        true
+    }
+}
+
+/// Where a row came from, written as the `source_` columns after the others.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct Provenance {
+    #[serde(rename = "source_url")]
+    pub url: String,
+    /// Missing if the page did not include its revision.
+    #[serde(rename = "source_revision")]
+    pub revision: Option<u64>,
+    /// When the page was fetched, in seconds since the Unix epoch.
+    #[serde(rename = "source_fetched")]
+    pub fetched: u64,
+    /// The heading anchor of the section, e.g. "Week_5".
+    #[serde(rename = "source_section")]
+    pub section: String,
+    /// The row in its table, where the header row is 1.
+    #[serde(rename = "source_row")]
+    pub row: u8,
+}
+
+/// A celebrity and their professional partners in a series, from the Couples
+/// table on the page. The weeks are found from the Week tables.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Couple {
+    pub series: u16,
+    pub celebrity: String,
+    /// The celebrity's Wikipedia article, as for [`Row::celebrity_id`].
+    pub celebrity_id: Option<String>,
+    #[serde(with = "empty_as_none")]
+    pub known_for: String,
+    /// The professionals who partnered the celebrity, separated by "; ".
+    pub professional: String,
+    /// The Wikipedia article of each professional, in the same order.
+    #[serde(with = "empty_as_none")]
+    pub professional_id: String,
+    /// The weeks each professional danced with the celebrity, e.g. "1-8; 9",
+    /// in the same order as `professional`.
+    #[serde(with = "empty_as_none")]
+    pub professional_weeks: String,
+    /// How the couple left the competition. Missing while they are still in it.
+    pub status: Option<Outcome>,
+    /// The last week the couple danced, e.g. the week they were eliminated.
+    pub last_week: Option<u16>,
+}
+impl Couple {
+    fn add_weeks(&mut self, rows: &[Row]) {
+        let rows: Vec<&Row> = rows
+            .iter()
+            .filter(|row| row.series == self.series && row.celebrity == self.celebrity)
+            .collect();
+        self.professional_weeks = self
+            .professional
+            .split("; ")
+            .map(|professional| {
+                let mut weeks: Vec<u16> = rows
+                    .iter()
+                    .filter(|row| row.professional == professional)
+                    .map(|row| row.week)
+                    .collect();
+                weeks.dedup();
+                week_ranges(&weeks)
+            })
+            .collect::<Vec<_>>()
+            .join("; ");
+        self.last_week = rows.iter().map(|row| row.week).max();
+    }
+}
+
+fn week_ranges(weeks: &[u16]) -> String {
+    // Write sorted weeks as ranges, e.g. [1, 2, 3, 5] as "1-3, 5".
+    let mut ranges: Vec<(u16, u16)> = Vec::new();
+    for &week in weeks {
+        match ranges.last_mut() {
+            Some((_, last)) if week == *last + 1 => *last = week,
+            _ => ranges.push((week, week)),
+        }
+    }
+    ranges
+        .iter()
+        .map(|&(first, last)| {
+            if first == last {
+                first.to_string()
+            } else {
+                format!("{}-{}", first, last)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[derive(Debug)]
+struct Output {
+    rows: Vec<Row>,
+    // From the Couples table.
+    couples: Vec<Couple>,
+    // In lenient mode, the errors that did not stop the extraction. In strict
+    // mode, this is `None` and the first error stops the extraction.
+    warnings: Option<Vec<ExtractError>>,
+}
+impl Output {
+    fn add(&mut self, row: Result<Option<Row>, ExtractError>) -> Result<(), ExtractError> {
+        match row {
+            Ok(Some(row)) => self.rows.push(row),
+            Ok(None) => {}
+            Err(error) => self.warn(error)?,
+        }
+        Ok(())
+    }
+    // Record an error as a warning, or return it in strict mode.
+    fn warn(&mut self, error: ExtractError) -> Result<(), ExtractError> {
+        match self.warnings {
+            Some(ref mut warnings) => warnings.push(error),
+            None => return Err(error),
+        }
+        Ok(())
+    }
+    fn is_in_progress(&self) -> bool {
+        // A series is over once a couple has been named the winner, in the
+        // results of the final or the Status column of the Couples table. A
+        // page without a Couples table, such as an extract of a page, has
+        // nothing to say the series is still running.
+        let winner = Some(Outcome::Winner);
+        !self.couples.is_empty()
+            && !self.couples.iter().any(|couple| couple.status == winner)
+            && !self.rows.iter().any(|row| row.result == winner)
+    }
+}
+
+/// The result for a couple in a week.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+pub enum Outcome {
+    Safe,
+    #[serde(rename = "Bottom two")]
+    BottomTwo,
+    Eliminated,
+    Withdrew,
+    Winner,
+    #[serde(rename = "Runner-up")]
+    RunnerUp,
+    #[serde(rename = "Third place")]
+    ThirdPlace,
+}
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Outcome::Safe => "Safe",
+            Outcome::BottomTwo => "Bottom two",
+            Outcome::Eliminated => "Eliminated",
+            Outcome::Withdrew => "Withdrew",
+            Outcome::Winner => "Winner",
+            Outcome::RunnerUp => "Runner-up",
+            Outcome::ThirdPlace => "Third place",
+        })
+    }
+}
+impl FromStr for Outcome {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "safe" => Ok(Outcome::Safe),
+            "bottom two" => Ok(Outcome::BottomTwo),
+            "eliminated" => Ok(Outcome::Eliminated),
+            "withdrew" | "withdrawn" => Ok(Outcome::Withdrew),
+            "winner" | "winners" => Ok(Outcome::Winner),
+            "runner-up" | "runners-up" => Ok(Outcome::RunnerUp),
+            "third place" => Ok(Outcome::ThirdPlace),
+            _ => Err(format!("unrecognized result {:?}", s)),
+        }
+    }
+}
+
+fn parse_result(result: &str) -> Result<Option<Outcome>, String> {
+    // The Result column is missing in some tables, so an empty result is not an error.
+    // An unrecognized result is returned as the error.
+    let result_decoded = html_escape::decode_html_entities(result);
+    match result_decoded.trim() {
+        "" => Ok(None),
+        result => match result.parse() {
+            Ok(outcome) => Ok(Some(outcome)),
+            Err(_) => Err(result.to_owned()),
+        },
+    }
+}
+
+fn wiki_id(href: &str) -> Option<String> {
+    // The title of the Wikipedia article a link goes to, e.g.
+    // "/wiki/Gorka_M%C3%A1rquez" -> "Gorka_Márquez". Links to pages that do
+    // not exist yet, and to other sites, have no ID.
+    let title = href.strip_prefix("/wiki/")?;
+    let title = title.split('#').next().unwrap_or_default();
+    let bytes = title.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let escaped = bytes
+            .get(i + 1..i + 3)
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match (bytes[i], escaped) {
+            (b'%', Some(byte)) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            (byte, _) => {
+                decoded.push(byte);
+                i += 1;
+            }
+        }
+    }
+    let title = String::from_utf8_lossy(&decoded).into_owned();
+    if title.is_empty() {
+        None
+    } else {
+        Some(title)
+    }
+}
+
+fn parse_status(status: &str) -> Option<Outcome> {
+    // The Status column of the Couples table, e.g. "Winners", "Third place" or
+    // "Eliminated 3rd;on 4 October 2020". Couples still in the competition
+    // have no status, or "Participating".
+    let status_decoded = html_escape::decode_html_entities(status);
+    let status = status_decoded
+        .split(';')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_lowercase();
+    if status.starts_with("winner") {
+        Some(Outcome::Winner)
+    } else if status.starts_with("runner") {
+        Some(Outcome::RunnerUp)
+    } else if status.starts_with("third") {
+        Some(Outcome::ThirdPlace)
+    } else if status.starts_with("eliminated") {
+        Some(Outcome::Eliminated)
+    } else if status.starts_with("withdr") {
+        Some(Outcome::Withdrew)
+    } else {
+        None
+    }
+}
+
+fn split_music(music: &str) -> (String, String) {
+    // Split the Music column into song titles and artists, e.g.
+    // "Boogie Woogie Bugle Boy"—The Andrews Sisters
+    // Medleys have each song on a separate line (separated here by semi-colons),
+    // or several quoted titles before a single artist. Multiple titles and
+    // artists are joined with " / ".
+    let music_decoded = html_escape::decode_html_entities(music);
+    let mut songs = Vec::<String>::new();
+    let mut artists = Vec::<String>::new();
+    for line in music_decoded.split(';') {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let (titles, artist) = match line.split_once('—') {
+            Some((titles, artist)) => (titles, artist.trim()),
+            None => (line, ""),
+        };
+        let titles = titles.replace(&['“', '”'][..], "\"");
+        if titles.contains('"') {
+            // Titles are quoted, so every second part is a title:
+            // "Song 1" / "Song 2" -> ["", "Song 1", " / ", "Song 2", ""]
+            songs.extend(
+                titles
+                    .split('"')
+                    .skip(1)
+                    .step_by(2)
+                    .map(|title| title.trim().to_owned()),
+            );
+        } else {
+            songs.push(titles.trim().to_owned());
+        }
+        if !artist.is_empty() {
+            // Use a consistent form for featured artists
+            let artist = artist
+                .replace(" featuring ", " feat. ")
+                .replace(" ft. ", " feat. ");
+            if !artists.contains(&artist) {
+                artists.push(artist);
+            }
+        }
+    }
+    songs.retain(|song| !song.is_empty());
+    (songs.join(" / "), artists.join(" / "))
+}
+
+#[derive(Debug, Default)]
+struct Judges {
+    series: Vec<String>,
+    week: Option<Vec<String>>,
+    in_week: bool,
+}
+impl Judges {
+    fn panel(&self) -> &[String] {
+        // A week section can name its own panel (e.g. a guest judge standing in),
+        // otherwise the panel from the series introduction applies.
+        self.week.as_ref().unwrap_or(&self.series)
+    }
+    fn start_week(&mut self) {
+        self.in_week = true;
+        self.week = None;
+    }
+    fn end_weeks(&mut self) {
+        self.in_week = false;
+        self.week = None;
+    }
+    fn add_paragraph(&mut self, paragraph: &str) {
+        // The judges' order is given in a sentence such as "...individual judges'
+        // scores in the charts below (given in parentheses) are listed in this order
+        // from left to right: Craig Revel Horwood, Motsi Mabuse, Shirley Ballas,
+        // Anton Du Beke."
+        const ORDER: &str = "order from left to right:";
+        if let Some((_, names)) = paragraph.split_once(ORDER) {
+            // Split returns at least one item so this `unwrap` will not panic
+            let names = names.split(&['.', '['][..]).next().unwrap();
+            let panel = names
+                .split(',')
+                .flat_map(|name| name.split(" and "))
+                .map(str::trim)
+                .filter(|name| !name.is_empty())
+                .map(str::to_owned)
+                .collect();
+            if self.in_week {
+                self.week = Some(panel);
+            } else {
+                self.series = panel;
+            }
+        }
+    }
+}
+
+/// Extract the scores from the Wikipedia page of a series, failing on the
+/// first row that cannot be extracted.
+pub fn extract_rows(series: u16, page: &str, aliases: &Aliases) -> Result<Vec<Row>, ExtractError> {
+    // Fail on the first row that cannot be extracted.
+    let output = extract(series, page, aliases, None)?;
+    Ok(output.rows)
+}
+
+/// The rows and couples of a series, from a single pass over its page.
+#[derive(Debug)]
+pub struct ExtractedSeries {
+    pub rows: Vec<Row>,
+    pub couples: Vec<Couple>,
+    // When not strict, the errors for rows that were skipped or kept without
+    // some of their values, and for tables that were ignored.
+    pub warnings: Vec<ExtractError>,
+}
+
+/// Extract the rows and the couples from the Wikipedia page of a series. If
+/// `strict`, fail on the first error, as [`extract_rows`] does. Otherwise
+/// return the errors as warnings: rows that cannot be extracted are skipped,
+/// as are tables that are not laid out as expected, and rows missing a value
+/// such as the result are kept without it. The weeks of a couple may be
+/// missing if their rows were skipped.
+pub fn extract_series(
+    series: u16,
+    page: &str,
+    aliases: &Aliases,
+    strict: bool,
+) -> Result<ExtractedSeries, ExtractError> {
+    let warnings = if strict { None } else { Some(Vec::new()) };
+    let output = extract(series, page, aliases, warnings)?;
+    Ok(ExtractedSeries {
+        rows: output.rows,
+        couples: output.couples,
+        warnings: output.warnings.unwrap_or_default(),
+    })
+}
+
+fn extract(
+    series: u16,
+    page: &str,
+    aliases: &Aliases,
+    warnings: Option<Vec<ExtractError>>,
+) -> Result<Output, ExtractError> {
+    // Cell mutability for shared and mutable access from multiple closures.
+    let output = Rc::new(RefCell::new(Output {
+        rows: vec![],
+        couples: vec![],
+        warnings,
+    }));
+    let people = Rc::new(RefCell::new(People::default()));
+    let aliases = Rc::new(aliases.clone());
+    let judges = Rc::new(RefCell::new(Judges::default()));
+    let paragraph = Rc::new(RefCell::new(String::new()));
+    let current_table = Rc::new(RefCell::new(
+        Box::new(UnrecognizedTable::new()) as Box<dyn TableHandler>
+    ));
+    let mut default_table_retainer: Option<Box<dyn TableHandler>> = None;
+
+    let element_content_handlers = vec![
+        // Find week number
+        element!("span.mw-headline", |el| {
+            if let Some(id) = el.get_attribute("id") {
+                let heading = Location {
+                    series,
+                    week: 0,
+                    row: 0,
+                };
+                if id == "Couples" {
+                    judges.borrow_mut().end_weeks();
+                    if default_table_retainer.is_some() {
+                        return Err(ExtractError::BadHeading(heading, id).into());
+                    }
+                    let prev_table = current_table.replace(Box::new(CoupleTable::new(
+                        output.clone(),
+                        people.clone(),
+                        aliases.clone(),
+                        series,
+                    )));
+                    default_table_retainer = Some(prev_table);
+                } else {
+                    let mut parts = id.split(&['_', ':'][..]);
+                    match parts.next() {
+                        Some("Week") => {
+                            // "Week_1", "Week_6:_Quarter-final"
+                            let week = match parts.next().map(u16::from_str) {
+                                Some(Ok(week)) => week,
+                                _ => return Err(ExtractError::BadHeading(heading, id).into()),
+                            };
+                            // "Quarter-final", "Movie Week", "Blackpool"
+                            let week_theme = match id.split_once(':') {
+                                Some((_, title)) => title.replace('_', " ").trim().to_owned(),
+                                None => String::new(),
+                            };
+                            judges.borrow_mut().start_week();
+                            let mut week_table = Box::new(WeekTable::new_for_week(
+                                output.clone(),
+                                people.clone(),
+                                aliases.clone(),
+                                judges.clone(),
+                                series,
+                                week,
+                            ));
+                            week_table.add_theme(&week_theme);
+                            let prev = current_table.replace(week_table);
+                            match default_table_retainer {
+                                Some(_) => {
+                                    // default is already in default_table_retainer, so
+                                    // previous table must be previous week.
+                                }
+                                None => {
+                                    default_table_retainer = Some(prev);
+                                }
+                            }
+                        }
+                        Some("Night" | "Show") => {
+                            // "Night_2_–_Latin", "Show_1" - multiple shows within a week,
+                            // keep the week as the current table but start a new show.
+                            if let Some(Ok(show)) = parts.next().map(u8::from_str) {
+                                let title = parts
+                                    .filter(|part| !part.is_empty() && *part != "–")
+                                    .collect::<Vec<_>>()
+                                    .join(" ");
+                                current_table.borrow_mut().show_begin(show, &title)?;
+                            }
+                        }
+                        _ => {
+                            judges.borrow_mut().end_weeks();
+                            // Use the default no-op table for any other sections.
+                            match default_table_retainer.take() {
+                                None => {
+                                    // current_table is already default
+                                }
+                                Some(default_table) => {
+                                    current_table.replace(default_table);
+                                }
+                            }
+                        }
+                    }
+                }
+                current_table.borrow_mut().section_begin(&id)?;
+            }
+            Ok(())
+        }),
+        element!("p", |p| {
+            // Collect the text of each paragraph to find the order of the judges
+            // and the theme of each week.
+            paragraph.borrow_mut().clear();
+            let text = paragraph.clone();
+            let paragraph_judges = judges.clone();
+            let table = current_table.clone();
+            p.on_end_tag(move |_| {
+                let text = html_escape::decode_html_entities(&*text.borrow()).into_owned();
+                paragraph_judges.borrow_mut().add_paragraph(&text);
+                table.borrow_mut().p_end(&text)
+            })?;
+            Ok(())
+        }),
+        element!("tr", |tr| {
+            let table = current_table.clone();
+            tr.on_end_tag(move |tr| table.borrow_mut().tr_end(tr))?;
+            current_table.borrow_mut().tr_begin(tr)
+        }),
+        element!("th", |th| {
+            let table = current_table.clone();
+            th.on_end_tag(move |th| table.borrow_mut().th_end(th))?;
+            current_table.borrow_mut().th_begin(th)
+        }),
+        element!("td", |td| {
+            let table = current_table.clone();
+            td.on_end_tag(move |td| table.borrow_mut().td_end(td))?;
+            current_table.borrow_mut().td_begin(td)
+        }),
+        element!("td a[href]", |a| current_table.borrow_mut().td_link(a)),
+        element!("td br", |td| {
+            // `<br />` is used to separate group dances and multiple professionals. In this
+            // case we replace the values with semi-colons to help parse later
+            current_table.borrow_mut().td_break(td)
+        }),
+        text!("td *", |t| {
+            // "<td>Anastacia &amp; Gorka<sup>1</sup>\n</td>"
+            // Set the user data for the text to skip the sub-element in the
+            // extracted text.  We only skip in the Week tables, where subelements
+            // are typically footnotes, or links in the Music column that we keep.
+            // In the Couples table most names are inside `a` subelements so we
+            // want to keep the text part.
+            t.set_user_data(SubElement::Other);
+            Ok(())
+        }),
+        text!("td sup", |t| {
+            t.set_user_data(SubElement::Footnote);
+            Ok(())
+        }),
+        text!("td", |t| { current_table.borrow_mut().td_text(t) }),
+        text!("th", |t| { current_table.borrow_mut().th_text(t) }),
+        text!("p", |t| {
+            paragraph.borrow_mut().push_str(t.as_str());
+            Ok(())
+        }),
+    ];
+
+    let mut rewriter = HtmlRewriter::new(
+        Settings {
+            element_content_handlers,
+            ..Settings::default()
+        },
+        |_: &[u8]| (),
+    );
+    rewriter.write(page.as_ref())?;
+    rewriter.end()?;
+    let mut result = output.replace(Output {
+        rows: vec![],
+        couples: vec![],
+        warnings: None,
+    });
+    if result.is_in_progress() {
+        for row in &mut result.rows {
+            row.partial = true;
+        }
+    }
+    for couple in &mut result.couples {
+        couple.add_weeks(&result.rows);
+    }
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::error::Error;
+    use std::format;
+
+    use super::{extract_rows, extract_series, ExtractedSeries, Outcome, Row};
+    use crate::aliases::Aliases;
+    use crate::error::{ExtractError, Location};
+
+    #[derive(Debug)]
+    struct TestError {}
+
+    impl std::fmt::Display for TestError {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            write!(f, "TestError")
+        }
+    }
+
+    impl Error for TestError {}
+
+    #[test]
+    fn test_extract_single_dance_per_couple() -> Result<(), Box<dyn Error>> {
+        let top = env!("CARGO_MANIFEST_DIR");
+        let page = std::fs::read_to_string(format!("{}/test-data/test1.html", top))?;
+        let expected_output = std::fs::read_to_string(format!("{}/test-data/test1.out", top))?;
+
+        let mut wtr = csv::Writer::from_writer(vec![]);
+        for row in extract_rows(1, &page, &Aliases::default())? {
+            wtr.serialize(row)?;
+        }
+        let actual_output = String::from_utf8(wtr.into_inner()?)?;
+        if expected_output == actual_output {
+            Ok(())
+        } else {
+            dbg!(expected_output);
+            dbg!(actual_output);
+            Err(Box::new(TestError {}))
+        }
+    }
+
+    #[test]
+    fn test_extract_multiple_dances_per_couple() -> Result<(), Box<dyn Error>> {
+        let top = env!("CARGO_MANIFEST_DIR");
+        let page = std::fs::read_to_string(format!("{}/test-data/test2.html", top))?;
+        let expected_output = std::fs::read_to_string(format!("{}/test-data/test2.out", top))?;
+
+        let mut wtr = csv::Writer::from_writer(vec![]);
+        for row in extract_rows(1, &page, &Aliases::default())? {
+            wtr.serialize(row)?;
+        }
+        let actual_output = String::from_utf8(wtr.into_inner()?)?;
+        if expected_output == actual_output {
+            Ok(())
+        } else {
+            dbg!(expected_output);
+            dbg!(actual_output);
+            Err(Box::new(TestError {}))
+        }
+    }
+
+    #[test]
+    fn test_extract_footnote() -> Result<(), Box<dyn Error>> {
+        let top = env!("CARGO_MANIFEST_DIR");
+        let page = std::fs::read_to_string(format!("{}/test-data/test3.html", top))?;
+        let expected_output = std::fs::read_to_string(format!("{}/test-data/test3.out", top))?;
+
+        let mut wtr = csv::Writer::from_writer(vec![]);
+        for row in extract_rows(1, &page, &Aliases::default())? {
+            wtr.serialize(row)?;
+        }
+        let actual_output = String::from_utf8(wtr.into_inner()?)?;
+        if expected_output == actual_output {
+            Ok(())
+        } else {
+            dbg!(expected_output);
+            dbg!(actual_output);
+            Err(Box::new(TestError {}))
+        }
+    }
+
+    #[test]
+    fn test_extract_judges() -> Result<(), Box<dyn Error>> {
+        let top = env!("CARGO_MANIFEST_DIR");
+        let page = std::fs::read_to_string(format!("{}/test-data/test4.html", top))?;
+        let expected_output = std::fs::read_to_string(format!("{}/test-data/test4.out", top))?;
+
+        let mut wtr = csv::Writer::from_writer(vec![]);
+        for row in extract_rows(17, &page, &Aliases::default())? {
+            wtr.serialize(row)?;
+        }
+        let actual_output = String::from_utf8(wtr.into_inner()?)?;
+        if expected_output == actual_output {
+            Ok(())
+        } else {
+            dbg!(expected_output);
+            dbg!(actual_output);
+            Err(Box::new(TestError {}))
+        }
+    }
+
+    #[test]
+    fn test_extract_unmatched_marks() -> Result<(), Box<dyn Error>> {
+        let top = env!("CARGO_MANIFEST_DIR");
+        let page = std::fs::read_to_string(format!("{}/test-data/test4.html", top))?;
+        let page = page.replace("29 (6,7,8,8)", "29 (6,7,8,8,0)");
+
+        // The row is kept with its total, but not the judges' marks.
+        let ExtractedSeries { rows, warnings, .. } =
+            extract_series(17, &page, &Aliases::default(), false)?;
+        assert_eq!(rows[0].total_score, 29);
+        assert_eq!(rows[0].score_count, 5);
+        assert_eq!(rows[0].craig, None);
+        assert_eq!(rows[0].motsi, None);
+        assert_eq!(rows[1].craig, Some(8));
+        assert_eq!(warnings.len(), 1);
+        assert!(matches!(warnings[0], ExtractError::UnmatchedMarks(_, _, _)));
+        assert!(extract_rows(17, &page, &Aliases::default()).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_extract_unknown_result() -> Result<(), Box<dyn Error>> {
+        let top = env!("CARGO_MANIFEST_DIR");
+        let page = std::fs::read_to_string(format!("{}/test-data/test11.html", top))?;
+        let page = page.replace("<td>Safe\n", "<td>Immune\n");
+
+        // The row is kept with its scores, but not the result.
+        let ExtractedSeries { rows, warnings, .. } =
+            extract_series(16, &page, &Aliases::default(), false)?;
+        assert_eq!(rows[0].celebrity, "Ashley");
+        assert_eq!(rows[0].total_score, 35);
+        assert_eq!(rows[0].result, None);
+        assert!(matches!(
+            &warnings[0],
+            ExtractError::UnknownResult(_, result) if result == "Immune"
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_rows() -> Result<(), Box<dyn Error>> {
+        // Rows read from the output are written back unchanged.
+        let top = env!("CARGO_MANIFEST_DIR");
+        let expected_output = std::fs::read_to_string(format!("{}/test-data/test4.out", top))?;
+
+        let mut rdr = csv::Reader::from_reader(expected_output.as_bytes());
+        let mut wtr = csv::Writer::from_writer(vec![]);
+        for row in rdr.deserialize() {
+            let row: Row = row?;
+            wtr.serialize(row)?;
+        }
+        let actual_output = String::from_utf8(wtr.into_inner()?)?;
+        assert_eq!(expected_output, actual_output);
+
+        // Older output has fewer columns.
+        let old_output =
+            "series,week,celebrity,professional,dance,total_score,score_count,avg_score,note\n\
+            1,1,Natasha Kaplinsky,Brendan Cole,Cha-Cha-Cha,27,4,6.75,\n";
+        let mut rdr = csv::Reader::from_reader(old_output.as_bytes());
+        let rows = rdr.deserialize().collect::<Result<Vec<Row>, _>>()?;
+        assert_eq!(rows[0].total_score, 27);
+        assert_eq!(rows[0].note, "");
+        assert_eq!(rows[0].craig, None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_extract_unknown_dance() -> Result<(), Box<dyn Error>> {
+        let top = env!("CARGO_MANIFEST_DIR");
+        let page = std::fs::read_to_string(format!("{}/test-data/test5.html", top))?;
+
+        match extract_rows(3, &page, &Aliases::default()) {
+            Err(ExtractError::UnknownDance(location, dance)) => {
+                assert_eq!(
+                    location,
+                    Location {
+                        series: 3,
+                        week: 2,
+                        row: 3
+                    }
+                );
+                assert_eq!(dance, "Hokey Cokey");
+                Ok(())
+            }
+            Err(error) => {
+                dbg!(error);
+                Err(Box::new(TestError {}))
+            }
+            Ok(rows) => {
+                dbg!(rows);
+                Err(Box::new(TestError {}))
+            }
+        }
+    }
+
+    #[test]
+    fn test_extract_lenient() -> Result<(), Box<dyn Error>> {
+        let top = env!("CARGO_MANIFEST_DIR");
+        let page = std::fs::read_to_string(format!("{}/test-data/test5.html", top))?;
+
+        let ExtractedSeries { rows, warnings, .. } =
+            extract_series(3, &page, &Aliases::default(), false)?;
+        assert_eq!(rows.len(), 2);
+        assert_eq!(warnings.len(), 1);
+        assert!(matches!(warnings[0], ExtractError::UnknownDance(_, _)));
+        Ok(())
+    }
+
+    #[test]
+    fn test_extract_provenance() -> Result<(), Box<dyn Error>> {
+        let top = env!("CARGO_MANIFEST_DIR");
+        let page = std::fs::read_to_string(format!("{}/test-data/test4.html", top))?;
+
+        let mut wtr = csv::Writer::from_writer(vec![]);
+        for mut row in extract_rows(17, &page, &Aliases::default())? {
+            row.add_provenance("https://example.org/series_17", Some(42), 1600000000);
+            // The CSV writer adds the provenance after the row in the same way.
+            let provenance = row.provenance.clone().ok_or(TestError {})?;
+            wtr.serialize((row, provenance))?;
+        }
+        let actual_output = String::from_utf8(wtr.into_inner()?)?;
+        let mut lines = actual_output.lines();
+        assert!(lines.next().unwrap_or_default().ends_with(
+            ",partial,dance_type,celebrity_id,professional_id,source_url,source_revision,source_fetched,source_section,source_row"
+        ));
+        assert!(lines
+            .next()
+            .unwrap_or_default()
+            .ends_with(",false,Latin,,,https://example.org/series_17,42,1600000000,Week_1,2"));
+        assert!(lines.last().unwrap_or_default().ends_with(
+            ",false,Latin,,,https://example.org/series_17,42,1600000000,Night_2_–_Latin,2"
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_extract_partial_series() -> Result<(), Box<dyn Error>> {
+        let top = env!("CARGO_MANIFEST_DIR");
+        let in_progress = std::fs::read_to_string(format!("{}/test-data/test5.html", top))?;
+        let finished = std::fs::read_to_string(format!("{}/test-data/test6.html", top))?;
+
+        let rows = extract_series(3, &in_progress, &Aliases::default(), false)?.rows;
+        assert!(rows.iter().all(|row| row.partial));
+        let rows = extract_rows(3, &finished, &Aliases::default())?;
+        assert_eq!(rows.len(), 2);
+        assert!(rows.iter().all(|row| !row.partial));
+
+        // The Couples table names the winner, though the Week tables do not.
+        let page = std::fs::read_to_string(format!("{}/test-data/test9.html", top))?;
+        let rows = extract_series(17, &page, &Aliases::default(), false)?.rows;
+        assert!(rows.iter().all(|row| row.result != Some(Outcome::Winner)));
+        assert!(rows.iter().all(|row| !row.partial));
+        Ok(())
+    }
+
+    #[test]
+    fn test_extract_columns_by_header() -> Result<(), Box<dyn Error>> {
+        let top = env!("CARGO_MANIFEST_DIR");
+        let page = std::fs::read_to_string(format!("{}/test-data/test7.html", top))?;
+
+        // Week 10 has an Order column, and Score after Dance and Music. Week 11
+        // has a table without Score or Dance columns, which stops a strict
+        // extraction, and is skipped otherwise.
+        let ExtractedSeries { rows, warnings, .. } =
+            extract_series(3, &page, &Aliases::default(), false)?;
+        assert_eq!(rows.len(), 3);
+        assert_eq!(warnings.len(), 1);
+        assert!(matches!(
+            &warnings[0],
+            ExtractError::UnexpectedTableShape(location, _) if location.week == 11
+        ));
+        match extract_rows(3, &page, &Aliases::default()) {
+            Err(ExtractError::UnexpectedTableShape(location, text)) => {
+                assert_eq!(location.week, 11);
+                assert!(text.contains("Judges' votes"));
+            }
+            Err(error) => {
+                dbg!(error);
+                return Err(Box::new(TestError {}));
+            }
+            Ok(rows) => {
+                dbg!(rows);
+                return Err(Box::new(TestError {}));
+            }
+        }
+        let week_10 = page
+            .split("<h3><span class=\"mw-headline\" id=\"Week_11\"")
+            .next();
+        let rows = extract_rows(3, week_10.unwrap_or_default(), &Aliases::default())?;
+        assert_eq!(rows.len(), 3);
+        assert_eq!(rows[0].celebrity, "Darren");
+        assert_eq!(rows[0].total_score, 39);
+        assert_eq!(rows[0].dance.to_string(), "Paso Doble");
+        assert_eq!(rows[0].song, "España cañi");
+        assert_eq!(rows[1].celebrity, "Darren");
+        assert_eq!(rows[1].total_score, 40);
+        assert_eq!(rows[1].dance.to_string(), "Showdance");
+        assert_eq!(rows[1].running_order, 2);
+        assert_eq!(rows[2].celebrity, "Colin");
+        assert_eq!(rows[2].professional, "Erin");
+        assert!(rows.iter().all(|row| !row.partial));
+        Ok(())
+    }
+
+    #[test]
+    fn test_extract_column_spans() -> Result<(), Box<dyn Error>> {
+        let top = env!("CARGO_MANIFEST_DIR");
+        let page = std::fs::read_to_string(format!("{}/test-data/test11.html", top))?;
+
+        // The group dance spans the Couple and Scores columns, and is not
+        // scored. The last Music cell spans the Result column.
+        let ExtractedSeries { rows, warnings, .. } =
+            extract_series(16, &page, &Aliases::default(), false)?;
+        assert_eq!(rows.len(), 3);
+        assert_eq!(rows[0].celebrity, "Ashley");
+        assert_eq!(rows[0].song, "Let's Fall in Love");
+        assert_eq!(rows[0].result, Some(Outcome::Safe));
+        assert_eq!(rows[1].celebrity, "Stacey");
+        assert_eq!(rows[1].total_score, 39);
+        assert_eq!(rows[1].dance.to_string(), "Tango");
+        assert_eq!(rows[1].running_order, 3);
+        assert_eq!(rows[1].song, "Libertango");
+        assert_eq!(rows[1].result, None);
+
+        // A row header in a data row is its first data cell.
+        assert_eq!(rows[2].celebrity, "Alex");
+        assert_eq!(rows[2].running_order, 4);
+        assert_eq!(rows[2].result, Some(Outcome::BottomTwo));
+
+        // A header cell within a data row is read as a data cell, and a cell
+        // with a bad span ignores the rest of the table.
+        assert_eq!(warnings.len(), 2);
+        assert!(matches!(warnings[0], ExtractError::BadScore(_, _)));
+        assert!(matches!(
+            warnings[1],
+            ExtractError::UnexpectedTableShape(_, _)
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_extract_couples() -> Result<(), Box<dyn Error>> {
+        let top = env!("CARGO_MANIFEST_DIR");
+        let page = std::fs::read_to_string(format!("{}/test-data/test8.html", top))?;
+
+        let couples = extract_series(17, &page, &Aliases::default(), false)?.couples;
+        let mut wtr = csv::Writer::from_writer(vec![]);
+        for couple in couples {
+            wtr.serialize(couple)?;
+        }
+        let actual_output = String::from_utf8(wtr.into_inner()?)?;
+        assert_eq!(
+            actual_output,
+            "series,celebrity,celebrity_id,known_for,professional,professional_id,professional_weeks,status,last_week\n\
+            17,Jason Bell,Jason_Bell,NFL player,Luba Mushtuk,Luba_Mushtuk,1,Eliminated,1\n\
+            17,Jamie Laing,Jamie_Laing,Made in Chelsea star,Karen Hauer; Gorka Márquez,Karen_Hauer; Gorka_Márquez,1; 2,Runner-up,2\n\
+            17,Kelvin Fletcher,Kelvin_Fletcher,Emmerdale actor,Oti Mabuse,Oti_Mabuse,1-2,Winner,2\n"
+        );
+
+        // The rows have the IDs of the people, whatever name they danced under.
+        let rows = extract_rows(17, &page, &Aliases::default())?;
+        assert_eq!(rows[1].celebrity_id.as_deref(), Some("Jamie_Laing"));
+        assert_eq!(rows[1].professional, "Karen Hauer");
+        assert_eq!(rows[1].professional_id.as_deref(), Some("Karen_Hauer"));
+        assert_eq!(rows[3].professional_id.as_deref(), Some("Gorka_Márquez"));
+
+        // A single pass gives both.
+        let extracted = extract_series(17, &page, &Aliases::default(), true)?;
+        assert_eq!(extracted.rows.len(), rows.len());
+        assert_eq!(extracted.couples.len(), 3);
+        assert!(extracted.warnings.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_extract_redirect_links() -> Result<(), Box<dyn Error>> {
+        let top = env!("CARGO_MANIFEST_DIR");
+        let page = std::fs::read_to_string(format!("{}/test-data/test10.html", top))?;
+
+        // Karen Clifton redirects to Karen Hauer's article, which the aliases
+        // know. The redirect for Aljaž is unknown, so he has no ID.
+        let rows = extract_rows(17, &page, &Aliases::default())?;
+        assert_eq!(rows[0].professional, "Karen Hauer");
+        assert_eq!(rows[0].professional_id.as_deref(), Some("Karen_Hauer"));
+        assert_eq!(rows[1].celebrity_id.as_deref(), Some("Emma_Weymouth"));
+        assert_eq!(rows[1].professional_id, None);
+        let couples = extract_series(17, &page, &Aliases::default(), false)?.couples;
+        assert_eq!(couples[0].professional_id, "Karen_Hauer");
+        assert_eq!(couples[1].professional_id, "");
+        Ok(())
+    }
+
+    #[test]
+    fn test_extract_shared_moniker() -> Result<(), Box<dyn Error>> {
+        let top = env!("CARGO_MANIFEST_DIR");
+        let page = std::fs::read_to_string(format!("{}/test-data/test9.html", top))?;
+
+        // The two Emmas are told apart by their professional partners.
+        let ExtractedSeries { rows, warnings, .. } =
+            extract_series(17, &page, &Aliases::default(), false)?;
+        let couples: Vec<_> = rows
+            .iter()
+            .map(|row| (row.celebrity.as_str(), row.professional.as_str()))
+            .collect();
+        assert_eq!(
+            couples,
+            vec![
+                ("Emma Barton", "Anton Du Beke"),
+                ("Emma Weymouth", "Aljaž Škorjanec"),
+                ("Emma", "Oti Mabuse"),
+                ("Kelvin Fletcher", "Oti Mabuse"),
+                ("Mike", "Oti Mabuse"),
+            ]
+        );
+        assert_eq!(rows[1].celebrity_id.as_deref(), Some("Emma_Weymouth"));
+        assert_eq!(rows[2].celebrity_id, None);
+        assert_eq!(rows[2].total_score, 20);
+
+        // Neither Emma danced with Oti, so that row keeps the moniker.
+        assert_eq!(warnings.len(), 2);
+        match &warnings[0] {
+            ExtractError::AmbiguousCouple(location, couple, candidates) => {
+                assert_eq!(location.row, 4);
+                assert_eq!(couple, "Emma & Oti");
+                assert_eq!(candidates, &["Emma Barton", "Emma Weymouth"]);
+            }
+            other => panic!("unexpected warning {}", other),
+        }
+        assert!(matches!(
+            &warnings[1],
+            ExtractError::UnresolvedCouple(_, couple) if couple == "Mike & Oti"
+        ));
+        assert!(matches!(
+            extract_rows(17, &page, &Aliases::default()),
+            Err(ExtractError::AmbiguousCouple(_, _, _))
+        ));
+        assert!(extract_series(17, &page, &Aliases::default(), true).is_err());
+        let extracted = extract_series(17, &page, &Aliases::default(), false)?;
+        assert_eq!(extracted.rows.len(), 5);
+        assert_eq!(extracted.warnings.len(), 2);
+        Ok(())
+    }
+}
@@ -0,0 +1,19 @@
+//! Extract Strictly Come Dancing (UK) scores from the show's Wikipedia pages.
+//!
+//! Each series has its own page, e.g.
+//! <https://en.wikipedia.org/wiki/Strictly_Come_Dancing_(series_1)>. Pass the
+//! HTML of the page to [`extract_rows`] to get a [`Row`] for each dance.
+
+mod aliases;
+mod dance;
+mod error;
+mod extract;
+mod registry;
+
+pub use aliases::Aliases;
+pub use dance::{Category, Dance, DanceStyle};
+pub use error::{ExtractError, Location};
+pub use extract::{
+    extract_rows, extract_series, Couple, ExtractedSeries, Outcome, Provenance, Row,
+};
+pub use registry::ProRegistry;
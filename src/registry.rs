@@ -0,0 +1,239 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::error::Error;
+use std::io;
+use std::path::Path;
+
+use crate::extract::{Couple, Row};
+
+// A professional dancing with a celebrity in a series, under the name they were
+// credited with on the page. People without a Wikipedia article have no ID.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Deserialize, Serialize)]
+struct Appearance {
+    id: String,
+    series: u16,
+    name: String,
+    celebrity: String,
+}
+impl Appearance {
+    fn key(&self) -> &str {
+        if self.id.is_empty() {
+            &self.name
+        } else {
+            &self.id
+        }
+    }
+}
+
+/// A record of the professionals in every series: the names they danced
+/// under, and their celebrity partners. Professionals are identified by their
+/// Wikipedia article, so it links their appearances across series even when
+/// they change their name, e.g. Karen Clifton and Karen Hauer. It is stored as
+/// CSV with one row for each professional and celebrity in a series.
+#[derive(Debug, Default)]
+pub struct ProRegistry {
+    appearances: BTreeSet<Appearance>,
+}
+impl ProRegistry {
+    /// Returns an empty registry if there is no file at `path`.
+    pub fn load(path: &Path) -> Result<ProRegistry, Box<dyn Error>> {
+        let mut rdr = match csv::Reader::from_path(path) {
+            Ok(rdr) => rdr,
+            Err(error) => match error.kind() {
+                csv::ErrorKind::Io(io_error) if io_error.kind() == io::ErrorKind::NotFound => {
+                    return Ok(ProRegistry::default());
+                }
+                _ => return Err(error.into()),
+            },
+        };
+        let mut registry = ProRegistry::default();
+        for appearance in rdr.deserialize() {
+            registry.appearances.insert(appearance?);
+        }
+        Ok(registry)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), Box<dyn Error>> {
+        let mut wtr = csv::Writer::from_path(path)?;
+        for appearance in &self.appearances {
+            wtr.serialize(appearance)?;
+        }
+        wtr.flush()?;
+        Ok(())
+    }
+
+    /// Record the professionals in the rows of a series, replacing anything
+    /// recorded for the series before. The rows must be as extracted from the
+    /// page, which keeps the name each professional was credited with even if
+    /// an alias renames them.
+    pub fn update(&mut self, series: u16, rows: &[Row]) {
+        self.appearances
+            .retain(|appearance| appearance.series != series);
+        for row in rows.iter().filter(|row| row.series == series) {
+            self.appearances.insert(Appearance {
+                id: row.professional_id.clone().unwrap_or_default(),
+                series,
+                name: row.credited_professional.clone(),
+                celebrity: row.celebrity.clone(),
+            });
+        }
+    }
+
+    fn appearances(&self, id: &str) -> Vec<&Appearance> {
+        self.appearances
+            .iter()
+            .filter(|appearance| appearance.key() == id)
+            .collect()
+    }
+
+    /// The name a professional danced under most recently.
+    pub fn name(&self, id: &str) -> Option<&str> {
+        self.appearances(id)
+            .into_iter()
+            .max_by_key(|appearance| appearance.series)
+            .map(|appearance| appearance.name.as_str())
+    }
+
+    /// The names a professional has danced under, oldest first.
+    pub fn names(&self, id: &str) -> Vec<&str> {
+        let mut names: Vec<&str> = Vec::new();
+        for appearance in self.appearances(id) {
+            if !names.contains(&appearance.name.as_str()) {
+                names.push(&appearance.name);
+            }
+        }
+        names
+    }
+
+    /// The series a professional has danced in.
+    pub fn series(&self, id: &str) -> Vec<u16> {
+        let mut series: Vec<u16> = self.appearances(id).iter().map(|a| a.series).collect();
+        series.dedup();
+        series
+    }
+
+    /// The celebrities a professional has partnered, with the series.
+    pub fn partners(&self, id: &str) -> Vec<(u16, &str)> {
+        self.appearances(id)
+            .into_iter()
+            .map(|appearance| (appearance.series, appearance.celebrity.as_str()))
+            .collect()
+    }
+
+    // The name to use for a professional credited as `name`, and a note if it
+    // is not the name they were credited with.
+    fn current_name(&self, id: Option<&str>, name: &str) -> Option<(String, String)> {
+        let current = self.name(id?)?;
+        if current == name {
+            return None;
+        }
+        let first_name = current.split(' ').next().unwrap_or_default();
+        Some((
+            current.to_owned(),
+            format!("{} danced as {}", first_name, name),
+        ))
+    }
+
+    /// Use the most recent name of each professional in the rows, so that
+    /// each professional has the same name in every series. Rows for a
+    /// professional credited under an earlier name get a note saying so.
+    pub fn apply(&self, rows: &mut [Row]) {
+        for row in rows {
+            if let Some((name, note)) =
+                self.current_name(row.professional_id.as_deref(), &row.professional)
+            {
+                if !row.note.is_empty() {
+                    row.note.push_str("; ");
+                }
+                row.note.push_str(&note);
+                row.professional = name;
+            }
+        }
+    }
+
+    /// Use the most recent name of each professional in the couples.
+    pub fn apply_to_couples(&self, couples: &mut [Couple]) {
+        for couple in couples {
+            let ids: Vec<&str> = couple.professional_id.split("; ").collect();
+            let names: Vec<String> = couple
+                .professional
+                .split("; ")
+                .enumerate()
+                .map(|(i, name)| {
+                    let id = ids.get(i).filter(|id| !id.is_empty()).copied();
+                    match self.current_name(id, name) {
+                        Some((current, _)) => current,
+                        None => name.to_owned(),
+                    }
+                })
+                .collect();
+            couple.professional = names.join("; ");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::error::Error;
+
+    use super::ProRegistry;
+    use crate::aliases::Aliases;
+    use crate::extract::{extract_rows, extract_series};
+
+    #[test]
+    fn test_registry() -> Result<(), Box<dyn Error>> {
+        let top = env!("CARGO_MANIFEST_DIR");
+        let page = std::fs::read_to_string(format!("{}/test-data/test8.html", top))?;
+        // Karen Hauer is credited as Karen Clifton in series 17, and under her
+        // own name in a later series. The registry records the name she was
+        // credited with, though the built-in alias renames her rows.
+        let aliases = Aliases::default();
+        let mut rows = extract_rows(17, &page, &aliases)?;
+        assert_eq!(rows[1].professional, "Karen Hauer");
+        assert_eq!(rows[1].credited_professional, "Karen Clifton");
+        let mut later = rows[1].clone();
+        later.series = 18;
+        later.celebrity = "Jason Bradbury".to_owned();
+        later.credited_professional = "Karen Hauer".to_owned();
+
+        let mut registry = ProRegistry::default();
+        registry.update(17, &rows);
+        registry.update(18, &[later]);
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("professionals.csv");
+        assert!(ProRegistry::load(&path)?.appearances.is_empty());
+        registry.save(&path)?;
+        let registry = ProRegistry::load(&path)?;
+
+        assert_eq!(registry.name("Karen_Hauer"), Some("Karen Hauer"));
+        assert_eq!(
+            registry.names("Karen_Hauer"),
+            vec!["Karen Clifton", "Karen Hauer"]
+        );
+        assert_eq!(registry.series("Karen_Hauer"), vec![17, 18]);
+        assert_eq!(
+            registry.partners("Karen_Hauer"),
+            vec![(17, "Jamie Laing"), (18, "Jason Bradbury")]
+        );
+        assert_eq!(registry.series("Oti_Mabuse"), vec![17]);
+
+        // Without the alias, the registry gives her rows her latest name.
+        let mut credited = rows.clone();
+        credited[1].professional = "Karen Clifton".to_owned();
+        credited[1].note.clear();
+        registry.apply(&mut credited);
+        assert_eq!(credited[1].professional, "Karen Hauer");
+        assert_eq!(credited[1].note, "Karen danced as Karen Clifton");
+        assert_eq!(credited[2].professional, "Oti Mabuse");
+        assert_eq!(credited[2].note, "");
+        // With it, the rows are already renamed and noted once.
+        registry.apply(&mut rows);
+        assert_eq!(rows[1].professional, "Karen Hauer");
+        assert_eq!(rows[1].note, "Karen danced as Karen Clifton");
+
+        let mut couples = extract_series(17, &page, &aliases, false)?.couples;
+        registry.apply_to_couples(&mut couples);
+        assert_eq!(couples[1].professional, "Karen Hauer; Gorka Márquez");
+        Ok(())
+    }
+}